@@ -0,0 +1,38 @@
+/* ------------------ Imports ----------------- */
+// Usages
+use crate::resim::Result;
+use std::fs;
+use std::path::Path;
+
+/* -------------------- Env ------------------- */
+/// Sets the given keys in a dotenv file, keeping any other lines as they are. `defaults` are only added when the file
+/// does not set them yet
+pub fn write_env(path: &Path, values: &[(&str, String)], defaults: &[(&str, String)]) -> Result<()> {
+    let existing: String = fs::read_to_string(path).unwrap_or_default();
+    let mut lines: Vec<String> = Vec::new();
+    let mut written: Vec<&str> = Vec::new();
+
+    for line in existing.lines() {
+        let key: &str = line.split('=').next().unwrap_or_default().trim();
+        match values.iter().find(|(name, _)| *name == key) {
+            Some((name, value)) => {
+                lines.push(format!("{}={}", name, value));
+                written.push(*name);
+            }
+            None => lines.push(line.to_string()),
+        }
+    }
+    for (name, value) in values.iter().filter(|(name, _)| !written.contains(name)) {
+        lines.push(format!("{}={}", name, value));
+    }
+    for (name, value) in defaults.iter() {
+        let set: bool = existing
+            .lines()
+            .any(|line| line.split('=').next().unwrap_or_default().trim() == *name);
+        if !set {
+            lines.push(format!("{}={}", name, value));
+        }
+    }
+
+    fs::write(path, lines.join("\n") + "\n").map_err(|error| format!("Failed to write {}: {}", path.display(), error))
+}
@@ -0,0 +1,219 @@
+/* ------------------ Imports ----------------- */
+// Modules
+mod env;
+mod resim;
+// Usages
+use crate::env::write_env;
+use crate::resim::{NewEntities, Resim, Result};
+use clap::Parser;
+use radish::shared::*;
+use radish_sdk::{MockTokenManifests, RadishManifests};
+use radix_transactions::prelude::*;
+use std::path::PathBuf;
+
+/* -------------------- Args ------------------ */
+/// Provisions a complete local Radish deployment into the resim simulator state
+#[derive(Parser, Debug)]
+#[command(name = "radish")]
+struct Args {
+    /// Directory of the Radish package to publish
+    #[arg(long, default_value = ".")]
+    package: PathBuf,
+    /// Dotenv file to write the deployed addresses into
+    #[arg(long, default_value = "../client/.env")]
+    env: PathBuf,
+    /// Directory the generated transaction manifests are written into
+    #[arg(long, default_value = "target/manifests")]
+    manifests: PathBuf,
+    /// Skip opening demo positions
+    #[arg(long)]
+    no_seed: bool,
+    /// Point the dApp definition at the deployment account even if the dotenv file already sets one
+    #[arg(long)]
+    set_dapp_definition: bool,
+}
+
+/// A demo collateral asset created for the deployment
+struct DemoAsset {
+    symbol: &'static str,
+    name: &'static str,
+    divisibility: u8,
+    supply: Decimal,
+    price: Decimal,
+}
+
+/* -------------------- Main ------------------ */
+fn main() {
+    let args: Args = Args::parse();
+
+    if let Err(error) = deploy(&args) {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
+
+fn deploy(args: &Args) -> Result<()> {
+    let resim: Resim = Resim::new(args.manifests.clone());
+
+    /* ------------------ Setup ------------------- */
+    println!("Resetting resim");
+    resim.reset()?;
+
+    let account_address: String = resim.new_account()?;
+    let account: ComponentAddress = resim.decode_component(&account_address)?;
+    println!("Account: {}", account_address);
+
+    let package_address: String = resim.publish(&args.package)?;
+    let package: PackageAddress = resim.decode_package(&package_address)?;
+    println!("Package: {}", package_address);
+
+    /* ---------------- Instantiate --------------- */
+    let entities: NewEntities = resim.run("instantiate", &RadishManifests::instantiate(package, account))?;
+    let component_address: String = entities
+        .components
+        .first()
+        .cloned()
+        .ok_or("Instantiation did not create a component")?;
+
+    let mut owner_badge: Option<String> = None;
+    let mut admin_badge: Option<String> = None;
+    let mut borrower_badge: Option<String> = None;
+    let mut depositor_badge: Option<String> = None;
+    let mut radish: Option<String> = None;
+    for resource in entities.resources.iter() {
+        let description: String = resim.show(resource)?;
+        // Check the badges first, as all of their names contain "Radish"
+        if description.contains("Radish Owner Badge") {
+            owner_badge = Some(resource.clone());
+        } else if description.contains("Radish Admin Badge") {
+            admin_badge = Some(resource.clone());
+        } else if description.contains("Radish Borrower Badge") {
+            borrower_badge = Some(resource.clone());
+        } else if description.contains("Radish Stability Pool Depositor Badge") {
+            depositor_badge = Some(resource.clone());
+        } else if description.contains("RSH") {
+            radish = Some(resource.clone());
+        }
+    }
+    let owner_badge: String = owner_badge.ok_or("Could not find the owner badge")?;
+    let admin_badge: String = admin_badge.ok_or("Could not find the admin badge")?;
+    let borrower_badge: String = borrower_badge.ok_or("Could not find the borrower badge")?;
+    let depositor_badge: String = depositor_badge.ok_or("Could not find the depositor badge")?;
+    let radish: String = radish.ok_or("Could not find the RSH resource")?;
+    println!("Component: {}", component_address);
+
+    let manifests: RadishManifests = RadishManifests::new(
+        resim.decode_component(&component_address)?,
+        resim.decode_resource(&radish)?,
+        resim.decode_resource(&borrower_badge)?,
+        resim.decode_resource(&depositor_badge)?,
+        resim.decode_resource(&admin_badge)?,
+    );
+
+    /* ---------------- Role Badges --------------- */
+    resim.run(
+        "mint_admin_badge",
+        &manifests.mint_admin_badges(account, resim.decode_resource(&owner_badge)?, dec!(1)),
+    )?;
+
+    /* ------------------ Assets ------------------ */
+    // Price data from OCISWAP at the time of writing
+    let demo_assets: Vec<DemoAsset> = vec![
+        DemoAsset {
+            symbol: "USDT",
+            name: "Tether USD",
+            divisibility: 6,
+            supply: dec!(1000000),
+            price: dec!(1.0),
+        },
+        DemoAsset {
+            symbol: "HUG",
+            name: "HUG",
+            divisibility: 18,
+            supply: dec!(1000000000),
+            price: dec!(0.0000109),
+        },
+    ];
+    let risk: RiskConfig = RiskConfig {
+        loan_to_value: dec!(0.5),
+        liquidation_threshold: dec!(0.65),
+        liquidation_bonus: dec!(0.1),
+        supply_cap: Decimal::MAX,
+    };
+
+    resim.run("set_price_xrd", &manifests.set_price(account, XRD, dec!(0.02126)))?;
+
+    let mut asset_addresses: Vec<(&str, String)> = vec![("XRD", resim.encode_resource(XRD)?)];
+    for asset in demo_assets.iter() {
+        // Mock tokens stand in for the testnet assets, with a faucet allowing the demo supply per day
+        let faucet: NewEntities = resim.run(
+            &format!("mock_token_{}", asset.symbol),
+            &MockTokenManifests::instantiate(package, account, asset.name, asset.symbol, asset.divisibility, asset.supply, 86400),
+        )?;
+        let address: String = faucet
+            .resources
+            .first()
+            .cloned()
+            .ok_or_else(|| format!("Could not find the {} resource", asset.symbol))?;
+        let faucet: ComponentAddress = resim.decode_component(faucet.components.first().ok_or("Could not find the mock token faucet")?)?;
+        let resource: ResourceAddress = resim.decode_resource(&address)?;
+        resim.run(
+            &format!("free_tokens_{}", asset.symbol),
+            &MockTokenManifests::free_tokens(faucet, account, asset.supply),
+        )?;
+
+        resim.run(&format!("add_asset_{}", asset.symbol), &manifests.add_asset(account, resource, risk.clone()))?;
+        resim.run(&format!("set_price_{}", asset.symbol), &manifests.set_price(account, resource, asset.price))?;
+
+        println!("{}: {}", asset.symbol, address);
+        asset_addresses.push((asset.symbol, address));
+    }
+
+    /* ----------------- Positions ---------------- */
+    if !args.no_seed {
+        let mut xrd_loan: AddrToAmount = AddrToAmount::new();
+        xrd_loan.insert(XRD, dec!(1000));
+        resim.run("seed_xrd_loan", &manifests.get_loan(account, &xrd_loan))?;
+
+        let mut mixed_loan: AddrToAmount = AddrToAmount::new();
+        for (symbol, address) in asset_addresses.iter().skip(1) {
+            let amount: Decimal = if *symbol == "USDT" { dec!(500) } else { dec!(10000000) };
+            mixed_loan.insert(resim.decode_resource(address)?, amount);
+        }
+        resim.run("seed_mixed_loan", &manifests.get_loan(account, &mixed_loan))?;
+        println!("Seeded demo positions");
+    }
+
+    /* -------------------- Env ------------------- */
+    // The dApp definition is usually a separately configured account, only default it to the deployment account
+    let dapp_definition: (&str, String) = ("NEXT_PUBLIC_DAPP_DEFINITION_ADDR", account_address.clone());
+    let mut values: Vec<(&str, String)> = vec![
+        ("NEXT_PUBLIC_COMPONENT_ADDR", component_address),
+        ("NEXT_PUBLIC_ACCOUNT_ADDR", account_address),
+        ("NEXT_PUBLIC_PACKAGE_ADDR", package_address),
+        ("NEXT_PUBLIC_OWNER_BADGE_ADDR", owner_badge),
+        ("NEXT_PUBLIC_ADMIN_BADGE_ADDR", admin_badge),
+        ("NEXT_PUBLIC_BORROWER_BADGE_ADDR", borrower_badge),
+        ("NEXT_PUBLIC_DEPOSITOR_BADGE_ADDR", depositor_badge),
+        ("NEXT_PUBLIC_RSH_ADDR", radish),
+    ];
+    let asset_keys: Vec<String> = asset_addresses
+        .iter()
+        .map(|(symbol, _)| format!("NEXT_PUBLIC_{}_ADDR", symbol))
+        .collect();
+    for ((_, address), key) in asset_addresses.into_iter().zip(asset_keys.iter()) {
+        values.push((key.as_str(), address));
+    }
+
+    let mut defaults: Vec<(&str, String)> = Vec::new();
+    if args.set_dapp_definition {
+        values.push(dapp_definition);
+    } else {
+        defaults.push(dapp_definition);
+    }
+
+    write_env(&args.env, &values, &defaults)?;
+    println!("Wrote addresses to {}", args.env.display());
+
+    Ok(())
+}
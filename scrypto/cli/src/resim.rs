@@ -0,0 +1,133 @@
+/* ------------------ Imports ----------------- */
+// Usages
+use radix_transactions::manifest::decompile;
+use radix_transactions::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/* ------------------- Types ------------------ */
+pub type Result<T> = std::result::Result<T, String>;
+
+/// Entities created by a committed transaction, as bech32 addresses
+#[derive(Debug, Default, Clone)]
+pub struct NewEntities {
+    pub components: Vec<String>,
+    pub resources: Vec<String>,
+}
+
+/* ------------------- Resim ------------------ */
+/// Thin wrapper around the `resim` binary, running typed manifests against the local simulator state
+pub struct Resim {
+    network: NetworkDefinition,
+    manifest_dir: PathBuf,
+}
+
+impl Resim {
+    pub fn new(manifest_dir: PathBuf) -> Self {
+        Self {
+            network: NetworkDefinition::simulator(),
+            manifest_dir,
+        }
+    }
+
+    pub fn reset(&self) -> Result<()> {
+        self.command(&["reset"]).map(|_| ())
+    }
+
+    /// Creates a new account, which becomes the default signer if it is the first one
+    pub fn new_account(&self) -> Result<String> {
+        let output: String = self.command(&["new-account"])?;
+        find_address(&output, "account_").ok_or_else(|| format!("No account address in resim output:\n{}", output))
+    }
+
+    pub fn publish(&self, package_dir: &Path) -> Result<String> {
+        let output: String = self.command(&["publish", &package_dir.to_string_lossy()])?;
+        find_address(&output, "package_").ok_or_else(|| format!("No package address in resim output:\n{}", output))
+    }
+
+    /// Returns the raw `resim show` description of an entity
+    pub fn show(&self, address: &str) -> Result<String> {
+        self.command(&["show", address])
+    }
+
+    /// Decompiles the manifest into the manifest directory and runs it with the default account
+    pub fn run(&self, name: &str, manifest: &TransactionManifestV1) -> Result<NewEntities> {
+        let source: String =
+            decompile(&manifest.instructions, &self.network).map_err(|error| format!("Failed to decompile {}: {:?}", name, error))?;
+
+        fs::create_dir_all(&self.manifest_dir).map_err(|error| error.to_string())?;
+        let path: PathBuf = self.manifest_dir.join(format!("{}.rtm", name));
+        fs::write(&path, source).map_err(|error| error.to_string())?;
+
+        let output: String = self.command(&["run", &path.to_string_lossy()])?;
+        if !output.contains("COMMITTED SUCCESS") {
+            return Err(format!("Transaction {} failed:\n{}", name, output));
+        }
+
+        Ok(parse_new_entities(&output))
+    }
+
+    /* ------------------ Addresses ----------------- */
+    pub fn encode_resource(&self, address: ResourceAddress) -> Result<String> {
+        AddressBech32Encoder::new(&self.network)
+            .encode(address.as_ref())
+            .map_err(|error| format!("{:?}", error))
+    }
+
+    pub fn decode_package(&self, address: &str) -> Result<PackageAddress> {
+        PackageAddress::try_from_bech32(&AddressBech32Decoder::new(&self.network), address)
+            .ok_or_else(|| format!("Invalid package address {}", address))
+    }
+
+    pub fn decode_component(&self, address: &str) -> Result<ComponentAddress> {
+        ComponentAddress::try_from_bech32(&AddressBech32Decoder::new(&self.network), address)
+            .ok_or_else(|| format!("Invalid component address {}", address))
+    }
+
+    pub fn decode_resource(&self, address: &str) -> Result<ResourceAddress> {
+        ResourceAddress::try_from_bech32(&AddressBech32Decoder::new(&self.network), address)
+            .ok_or_else(|| format!("Invalid resource address {}", address))
+    }
+
+    /* ------------------- Helpers ------------------ */
+    fn command(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new("resim")
+            .args(args)
+            .output()
+            .map_err(|error| format!("Failed to run resim: {}", error))?;
+        let stdout: String = String::from_utf8_lossy(&output.stdout).into_owned();
+
+        if !output.status.success() {
+            return Err(format!(
+                "resim {} failed:\n{}{}",
+                args.join(" "),
+                stdout,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(stdout)
+    }
+}
+
+/* ------------------ Parsing ----------------- */
+/// Finds the first whitespace separated token starting with the given address prefix
+fn find_address(output: &str, prefix: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .find(|token| token.starts_with(prefix))
+        .map(|token| token.trim_end_matches(|c: char| !c.is_alphanumeric()).to_string())
+}
+
+/// Collects the addresses listed under "New Entities" in a transaction receipt
+fn parse_new_entities(output: &str) -> NewEntities {
+    let mut entities: NewEntities = NewEntities::default();
+    for line in output.lines() {
+        if line.contains("Component: ") {
+            entities.components.extend(find_address(line, "component_"));
+        } else if line.contains("Resource: ") {
+            entities.resources.extend(find_address(line, "resource_"));
+        }
+    }
+    entities
+}
@@ -0,0 +1,118 @@
+/* ------------------ Imports ----------------- */
+// Usages
+use radish::events::*;
+use radish::shared::*;
+use radix_transactions::prelude::*;
+
+/* ------------------ Outputs ----------------- */
+// Methods returning buckets have no useful off-ledger output, their results show up as account deposits instead
+
+pub fn decode_estimate_loan(output: &[u8]) -> Result<Decimal, DecodeError> {
+    scrypto_decode::<Decimal>(output)
+}
+
+pub fn decode_estimate_repay(output: &[u8]) -> Result<AddrToAmount, DecodeError> {
+    scrypto_decode::<AddrToAmount>(output)
+}
+
+/// Decodes the output of any of the `preview_*` methods
+pub fn decode_position_preview(output: &[u8]) -> Result<PositionPreview, DecodeError> {
+    scrypto_decode::<PositionPreview>(output)
+}
+
+pub fn decode_protocol_stats(output: &[u8]) -> Result<ProtocolStats, DecodeError> {
+    scrypto_decode::<ProtocolStats>(output)
+}
+
+pub fn decode_list_assets(output: &[u8]) -> Result<AssetPage, DecodeError> {
+    scrypto_decode::<AssetPage>(output)
+}
+
+pub fn decode_split_preview(output: &[u8]) -> Result<(PositionPreview, PositionPreview), DecodeError> {
+    scrypto_decode::<(PositionPreview, PositionPreview)>(output)
+}
+
+/// Decodes the output of `get_health_many` and `get_riskiest_positions`
+pub fn decode_health_many(output: &[u8]) -> Result<Vec<PositionHealth>, DecodeError> {
+    scrypto_decode::<Vec<PositionHealth>>(output)
+}
+
+/// Decodes the output of `preview_rebalance`, the source then the destination position
+pub fn decode_rebalance_preview(output: &[u8]) -> Result<(PositionPreview, PositionPreview), DecodeError> {
+    scrypto_decode::<(PositionPreview, PositionPreview)>(output)
+}
+
+pub fn decode_redemption_preview(output: &[u8]) -> Result<RedemptionPlan, DecodeError> {
+    scrypto_decode::<RedemptionPlan>(output)
+}
+
+pub fn decode_pool_deposit(output: &[u8]) -> Result<PoolDeposit, DecodeError> {
+    scrypto_decode::<PoolDeposit>(output)
+}
+
+pub fn decode_position_history(output: &[u8]) -> Result<HistoryPage, DecodeError> {
+    scrypto_decode::<HistoryPage>(output)
+}
+
+pub fn decode_delegates(output: &[u8]) -> Result<Vec<(ResourceOrNonFungible, DelegatePermissions)>, DecodeError> {
+    scrypto_decode::<Vec<(ResourceOrNonFungible, DelegatePermissions)>>(output)
+}
+
+/* ------------------ Events ------------------ */
+/// Every event emitted by the Radish component
+#[derive(Debug, Clone)]
+pub enum RadishEvent {
+    AssetAdded(AssetAddedEvent),
+    AssetRemoved(AssetRemovedEvent),
+    AssetUpdated(AssetUpdatedEvent),
+    Supply(SupplyEvent),
+    Withdraw(WithdrawEvent),
+    Borrow(BorrowEvent),
+    Repay(RepayEvent),
+    Liquidate(LiquidateEvent),
+    Redemption(RedemptionEvent),
+    PoolDeposit(PoolDepositEvent),
+    PoolWithdraw(PoolWithdrawEvent),
+    PoolAbsorb(PoolAbsorbEvent),
+    Merge(MergeEvent),
+    Split(SplitEvent),
+    Delegate(DelegateEvent),
+    Rebalance(RebalanceEvent),
+    InterestAccrual(InterestAccrualEvent),
+    OracleUpdate(OracleUpdateEvent),
+    PauseToggle(PauseToggleEvent),
+    ParameterChange(ParameterChangeEvent),
+}
+
+impl RadishEvent {
+    /// Decodes an event by its name, as found in the component's blueprint schema
+    ///
+    /// Returns `Ok(None)` for events not emitted by Radish
+    pub fn decode(name: &str, data: &[u8]) -> Result<Option<Self>, DecodeError> {
+        let event: Self = match name {
+            "AssetAddedEvent" => Self::AssetAdded(scrypto_decode(data)?),
+            "AssetRemovedEvent" => Self::AssetRemoved(scrypto_decode(data)?),
+            "AssetUpdatedEvent" => Self::AssetUpdated(scrypto_decode(data)?),
+            "SupplyEvent" => Self::Supply(scrypto_decode(data)?),
+            "WithdrawEvent" => Self::Withdraw(scrypto_decode(data)?),
+            "BorrowEvent" => Self::Borrow(scrypto_decode(data)?),
+            "RepayEvent" => Self::Repay(scrypto_decode(data)?),
+            "LiquidateEvent" => Self::Liquidate(scrypto_decode(data)?),
+            "RedemptionEvent" => Self::Redemption(scrypto_decode(data)?),
+            "PoolDepositEvent" => Self::PoolDeposit(scrypto_decode(data)?),
+            "PoolWithdrawEvent" => Self::PoolWithdraw(scrypto_decode(data)?),
+            "PoolAbsorbEvent" => Self::PoolAbsorb(scrypto_decode(data)?),
+            "MergeEvent" => Self::Merge(scrypto_decode(data)?),
+            "SplitEvent" => Self::Split(scrypto_decode(data)?),
+            "DelegateEvent" => Self::Delegate(scrypto_decode(data)?),
+            "RebalanceEvent" => Self::Rebalance(scrypto_decode(data)?),
+            "InterestAccrualEvent" => Self::InterestAccrual(scrypto_decode(data)?),
+            "OracleUpdateEvent" => Self::OracleUpdate(scrypto_decode(data)?),
+            "PauseToggleEvent" => Self::PauseToggle(scrypto_decode(data)?),
+            "ParameterChangeEvent" => Self::ParameterChange(scrypto_decode(data)?),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(event))
+    }
+}
@@ -0,0 +1,42 @@
+/* ------------------ Imports ----------------- */
+// Usages
+pub use radish::errors::RadishError;
+use radish::errors::ERROR_TAG;
+use radix_transactions::prelude::*;
+
+/* ------------------ Errors ------------------ */
+// The component panics with `[RadishError <code>:<hex>] <description>`, which ends up (possibly escaped) in the
+// receipt's failure reason or the gateway's error message
+
+/// Finds the Radish error in a failed transaction's error message, e.g. `format!("{:?}", receipt)` or a gateway
+/// `error_message`
+pub fn decode_error(message: &str) -> Option<RadishError> {
+    let (_code, payload) = parse_error(message)?;
+    scrypto_decode::<RadishError>(&payload).ok()
+}
+
+/// Finds the code of the Radish error in a failed transaction's error message
+///
+/// ! Also works for errors this version of the SDK cannot decode, such as variants added by a newer component
+pub fn decode_error_code(message: &str) -> Option<u16> {
+    parse_error(message).map(|(code, _payload)| code)
+}
+
+/* ------------------ Parsing ----------------- */
+fn parse_error(message: &str) -> Option<(u16, Vec<u8>)> {
+    let start: usize = message.find(&format!("[{} ", ERROR_TAG))? + ERROR_TAG.len() + 2;
+    let (code, rest) = message[start..].split_once(':')?;
+    let payload: &str = &rest[..rest.find(']')?];
+
+    Some((code.parse().ok()?, decode_hex(payload)?))
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,29 @@
+/* ------------------ Imports ----------------- */
+// Usages
+use radish::math;
+use radish::shared::*;
+use radix_transactions::prelude::*;
+
+/* ----------------- Estimates ---------------- */
+// Off-chain counterparts of the blueprint's valuation, computed with the same math module from `list_assets` data
+
+/// RSH which may be borrowed against the collateral, or `None` if an asset is unlisted or unpriced
+pub fn estimate_loan(collateral: &AddrToAmount, assets: &[AssetListing], radish_price: Decimal) -> Option<Decimal> {
+    let value: Decimal = weighted_value(collateral, assets, |risk| risk.loan_to_value)?;
+    Some(math::borrow_capacity(value, radish_price))
+}
+
+/// Health factor of a position, or `None` if an asset is unlisted or unpriced
+pub fn health_factor(collateral: &AddrToAmount, debt: Decimal, assets: &[AssetListing], radish_price: Decimal) -> Option<Decimal> {
+    let value: Decimal = weighted_value(collateral, assets, |risk| risk.liquidation_threshold)?;
+    Some(math::health_factor(value, debt, radish_price))
+}
+
+fn weighted_value(collateral: &AddrToAmount, assets: &[AssetListing], weight: impl Fn(&RiskConfig) -> Decimal) -> Option<Decimal> {
+    let mut value: Decimal = Decimal::ZERO;
+    for (address, &amount) in collateral.iter() {
+        let listing: &AssetListing = assets.iter().find(|listing| listing.asset == *address)?;
+        value = value.checked_add(math::collateral_value(amount, listing.price?, weight(&listing.risk)))?;
+    }
+    Some(value)
+}
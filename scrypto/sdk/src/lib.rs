@@ -0,0 +1,13 @@
+/* ------------------ Imports ----------------- */
+// Modules
+pub mod decode;
+pub mod errors;
+pub mod estimate;
+pub mod manifests;
+// Usages
+pub use crate::decode::*;
+pub use crate::errors::*;
+pub use crate::manifests::*;
+pub use radish::events;
+pub use radish::math;
+pub use radish::shared;
@@ -0,0 +1,886 @@
+/* ------------------ Imports ----------------- */
+// Usages
+use radish::shared::*;
+use radix_transactions::prelude::*;
+
+/* ----------------- Constants ---------------- */
+pub const BLUEPRINT_NAME: &str = "Radish";
+pub const MOCK_TOKEN_BLUEPRINT_NAME: &str = "MockToken";
+pub const DEFAULT_FEE: Decimal = Decimal::ONE_HUNDRED;
+
+/* -------------- Builder Extension ----------- */
+/// Appends calls to the Radish blueprint to a manifest
+///
+/// Methods taking buckets expect them to have already been named on the worktop, e.g. with `take_from_worktop`
+pub trait RadishManifestBuilder: Sized {
+    fn radish_instantiate(self, package: PackageAddress) -> Self;
+
+    // Admin
+    fn radish_add_asset(self, component: ComponentAddress, asset: ResourceAddress, risk: RiskConfig) -> Self;
+    fn radish_remove_asset(self, component: ComponentAddress, asset: ResourceAddress) -> Self;
+    fn radish_update_asset(self, component: ComponentAddress, asset: ResourceAddress, status: AssetStatus, risk: RiskConfig) -> Self;
+    fn radish_set_price(self, component: ComponentAddress, asset: ResourceAddress, price: Decimal) -> Self;
+    fn radish_set_paused(self, component: ComponentAddress, paused: bool) -> Self;
+    fn radish_set_parameters(self, component: ComponentAddress, parameters: ProtocolParameters) -> Self;
+
+    // Lending
+    fn radish_accrue_interest(self, component: ComponentAddress) -> Self;
+    fn radish_estimate_loan(self, component: ComponentAddress, collateral: AddrToAmount) -> Self;
+    fn radish_get_loan(self, component: ComponentAddress, collateral_buckets: Vec<String>) -> Self;
+    fn radish_add_collateral(self, component: ComponentAddress, position_proof: &str, collateral_buckets: Vec<String>) -> Self;
+    fn radish_borrow_more(self, component: ComponentAddress, position_proof: &str, amount: Decimal) -> Self;
+    fn radish_withdraw_collateral(self, component: ComponentAddress, position_proof: &str, asset: ResourceAddress, amount: Decimal) -> Self;
+    fn radish_repay_targeted(self, component: ComponentAddress, position_proof: &str, repayment_bucket: &str, asset: ResourceAddress) -> Self;
+    fn radish_estimate_repay(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> Self;
+    fn radish_repay_loan(self, component: ComponentAddress, position_proof: &str, repayment_bucket: &str) -> Self;
+    fn radish_repay_many(
+        self,
+        component: ComponentAddress,
+        positions_proof: &str,
+        repayments: Vec<(NonFungibleLocalId, Decimal)>,
+        repayment_bucket: &str,
+    ) -> Self;
+    fn radish_close_position(self, component: ComponentAddress, borrower_bucket: &str) -> Self;
+    fn radish_merge_positions(self, component: ComponentAddress, positions_proof: &str) -> Self;
+    fn radish_split_position(self, component: ComponentAddress, position_proof: &str, fraction: Decimal) -> Self;
+    fn radish_repay_for(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, repayment_bucket: &str) -> Self;
+    fn radish_liquidate(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, repayment_bucket: &str) -> Self;
+    fn radish_redeem(self, component: ComponentAddress, redemption_bucket: &str) -> Self;
+
+    // Delegation
+    fn radish_set_delegate(
+        self,
+        component: ComponentAddress,
+        position_proof: &str,
+        holder: ComponentAddress,
+        delegate: ResourceOrNonFungible,
+        permissions: DelegatePermissions,
+    ) -> Self;
+    fn radish_delegate_add_collateral(
+        self,
+        component: ComponentAddress,
+        delegate: ResourceOrNonFungible,
+        borrower_id: NonFungibleLocalId,
+        collateral_buckets: Vec<String>,
+    ) -> Self;
+    fn radish_delegate_rebalance(
+        self,
+        component: ComponentAddress,
+        delegate: ResourceOrNonFungible,
+        from: NonFungibleLocalId,
+        to: NonFungibleLocalId,
+        asset: ResourceAddress,
+        amount: Decimal,
+    ) -> Self;
+
+    // Stability Pool
+    fn radish_deposit_to_pool(self, component: ComponentAddress, deposit_bucket: &str) -> Self;
+    fn radish_withdraw_from_pool(self, component: ComponentAddress, depositor_proof: &str, amount: Decimal) -> Self;
+    fn radish_liquidate_with_pool(self, component: ComponentAddress, borrower_id: NonFungibleLocalId) -> Self;
+
+    // Previews
+    fn radish_preview_supply(self, component: ComponentAddress, collateral: AddrToAmount) -> Self;
+    fn radish_preview_withdraw(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> Self;
+    fn radish_preview_borrow_more(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, amount: Decimal) -> Self;
+    fn radish_preview_add_collateral(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, collateral: AddrToAmount) -> Self;
+    fn radish_preview_withdraw_collateral(
+        self,
+        component: ComponentAddress,
+        borrower_id: NonFungibleLocalId,
+        asset: ResourceAddress,
+        amount: Decimal,
+    ) -> Self;
+    fn radish_preview_repay_targeted(
+        self,
+        component: ComponentAddress,
+        borrower_id: NonFungibleLocalId,
+        repayment: Decimal,
+        asset: ResourceAddress,
+    ) -> Self;
+    fn radish_preview_repay_for(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> Self;
+    fn radish_preview_liquidate(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> Self;
+    fn radish_preview_redeem(self, component: ComponentAddress, redemption: Decimal) -> Self;
+    fn radish_preview_merge_positions(self, component: ComponentAddress, borrower_ids: Vec<NonFungibleLocalId>) -> Self;
+    fn radish_preview_split_position(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, fraction: Decimal) -> Self;
+    fn radish_preview_rebalance(
+        self,
+        component: ComponentAddress,
+        from: NonFungibleLocalId,
+        to: NonFungibleLocalId,
+        asset: ResourceAddress,
+        amount: Decimal,
+    ) -> Self;
+
+    // Read
+    fn radish_get_protocol_stats(self, component: ComponentAddress) -> Self;
+    fn radish_list_assets(self, component: ComponentAddress, start: Decimal, limit: u32) -> Self;
+    fn radish_get_health_many(self, component: ComponentAddress, borrower_ids: Vec<NonFungibleLocalId>) -> Self;
+    fn radish_get_delegates(self, component: ComponentAddress, borrower_id: NonFungibleLocalId) -> Self;
+    fn radish_refresh_position(self, component: ComponentAddress, borrower_id: NonFungibleLocalId) -> Self;
+    fn radish_get_position_history(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, cursor: u64, limit: u32) -> Self;
+    fn radish_get_riskiest_positions(self, component: ComponentAddress, limit: u32) -> Self;
+    fn radish_get_pool_deposit(self, component: ComponentAddress, depositor_id: NonFungibleLocalId) -> Self;
+}
+
+impl RadishManifestBuilder for ManifestBuilder {
+    fn radish_instantiate(self, package: PackageAddress) -> Self {
+        self.call_function(package, BLUEPRINT_NAME, "instantiate", manifest_args!())
+    }
+
+    fn radish_add_asset(self, component: ComponentAddress, asset: ResourceAddress, risk: RiskConfig) -> Self {
+        self.call_method(component, "add_asset", manifest_args!(asset, risk))
+    }
+
+    fn radish_remove_asset(self, component: ComponentAddress, asset: ResourceAddress) -> Self {
+        self.call_method(component, "remove_asset", manifest_args!(asset))
+    }
+
+    fn radish_update_asset(self, component: ComponentAddress, asset: ResourceAddress, status: AssetStatus, risk: RiskConfig) -> Self {
+        self.call_method(component, "update_asset", manifest_args!(asset, status, risk))
+    }
+
+    fn radish_set_price(self, component: ComponentAddress, asset: ResourceAddress, price: Decimal) -> Self {
+        self.call_method(component, "set_price", manifest_args!(asset, price))
+    }
+
+    fn radish_set_paused(self, component: ComponentAddress, paused: bool) -> Self {
+        self.call_method(component, "set_paused", manifest_args!(paused))
+    }
+
+    fn radish_set_parameters(self, component: ComponentAddress, parameters: ProtocolParameters) -> Self {
+        self.call_method(component, "set_parameters", manifest_args!(parameters))
+    }
+
+    fn radish_accrue_interest(self, component: ComponentAddress) -> Self {
+        self.call_method(component, "accrue_interest", manifest_args!())
+    }
+
+    fn radish_estimate_loan(self, component: ComponentAddress, collateral: AddrToAmount) -> Self {
+        self.call_method(component, "estimate_loan", manifest_args!(collateral))
+    }
+
+    fn radish_get_loan(self, component: ComponentAddress, collateral_buckets: Vec<String>) -> Self {
+        self.call_method_with_name_lookup(component, "get_loan", |lookup| {
+            let buckets: Vec<ManifestBucket> = collateral_buckets.iter().map(|name| lookup.bucket(name)).collect();
+            (buckets,)
+        })
+    }
+
+    fn radish_add_collateral(self, component: ComponentAddress, position_proof: &str, collateral_buckets: Vec<String>) -> Self {
+        self.call_method_with_name_lookup(component, "add_collateral", |lookup| {
+            let buckets: Vec<ManifestBucket> = collateral_buckets.iter().map(|name| lookup.bucket(name)).collect();
+            (lookup.proof(position_proof), buckets)
+        })
+    }
+
+    fn radish_borrow_more(self, component: ComponentAddress, position_proof: &str, amount: Decimal) -> Self {
+        self.call_method_with_name_lookup(component, "borrow_more", |lookup| (lookup.proof(position_proof), amount))
+    }
+
+    fn radish_withdraw_collateral(self, component: ComponentAddress, position_proof: &str, asset: ResourceAddress, amount: Decimal) -> Self {
+        self.call_method_with_name_lookup(component, "withdraw_collateral", |lookup| {
+            (lookup.proof(position_proof), asset, amount)
+        })
+    }
+
+    fn radish_repay_targeted(self, component: ComponentAddress, position_proof: &str, repayment_bucket: &str, asset: ResourceAddress) -> Self {
+        self.call_method_with_name_lookup(component, "repay_targeted", |lookup| {
+            (lookup.proof(position_proof), lookup.bucket(repayment_bucket), asset)
+        })
+    }
+
+    fn radish_estimate_repay(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> Self {
+        self.call_method(component, "estimate_repay", manifest_args!(borrower_id, repayment))
+    }
+
+    fn radish_repay_loan(self, component: ComponentAddress, position_proof: &str, repayment_bucket: &str) -> Self {
+        self.call_method_with_name_lookup(component, "repay_loan", |lookup| {
+            (lookup.proof(position_proof), lookup.bucket(repayment_bucket))
+        })
+    }
+
+    fn radish_repay_many(
+        self,
+        component: ComponentAddress,
+        positions_proof: &str,
+        repayments: Vec<(NonFungibleLocalId, Decimal)>,
+        repayment_bucket: &str,
+    ) -> Self {
+        self.call_method_with_name_lookup(component, "repay_many", |lookup| {
+            (lookup.proof(positions_proof), repayments, lookup.bucket(repayment_bucket))
+        })
+    }
+
+    fn radish_close_position(self, component: ComponentAddress, borrower_bucket: &str) -> Self {
+        self.call_method_with_name_lookup(component, "close_position", |lookup| (lookup.bucket(borrower_bucket),))
+    }
+
+    fn radish_merge_positions(self, component: ComponentAddress, positions_proof: &str) -> Self {
+        self.call_method_with_name_lookup(component, "merge_positions", |lookup| (lookup.proof(positions_proof),))
+    }
+
+    fn radish_split_position(self, component: ComponentAddress, position_proof: &str, fraction: Decimal) -> Self {
+        self.call_method_with_name_lookup(component, "split_position", |lookup| (lookup.proof(position_proof), fraction))
+    }
+
+    fn radish_repay_for(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, repayment_bucket: &str) -> Self {
+        self.call_method_with_name_lookup(component, "repay_for", |lookup| (borrower_id, lookup.bucket(repayment_bucket)))
+    }
+
+    fn radish_liquidate(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, repayment_bucket: &str) -> Self {
+        self.call_method_with_name_lookup(component, "liquidate", |lookup| (borrower_id, lookup.bucket(repayment_bucket)))
+    }
+
+    fn radish_redeem(self, component: ComponentAddress, redemption_bucket: &str) -> Self {
+        self.call_method_with_name_lookup(component, "redeem", |lookup| (lookup.bucket(redemption_bucket),))
+    }
+
+    fn radish_deposit_to_pool(self, component: ComponentAddress, deposit_bucket: &str) -> Self {
+        self.call_method_with_name_lookup(component, "deposit_to_pool", |lookup| (lookup.bucket(deposit_bucket),))
+    }
+
+    fn radish_withdraw_from_pool(self, component: ComponentAddress, depositor_proof: &str, amount: Decimal) -> Self {
+        self.call_method_with_name_lookup(component, "withdraw_from_pool", |lookup| (lookup.proof(depositor_proof), amount))
+    }
+
+    fn radish_liquidate_with_pool(self, component: ComponentAddress, borrower_id: NonFungibleLocalId) -> Self {
+        self.call_method(component, "liquidate_with_pool", manifest_args!(borrower_id))
+    }
+
+    fn radish_set_delegate(
+        self,
+        component: ComponentAddress,
+        position_proof: &str,
+        holder: ComponentAddress,
+        delegate: ResourceOrNonFungible,
+        permissions: DelegatePermissions,
+    ) -> Self {
+        self.call_method_with_name_lookup(component, "set_delegate", |lookup| {
+            (lookup.proof(position_proof), holder, delegate, permissions)
+        })
+    }
+
+    fn radish_delegate_add_collateral(
+        self,
+        component: ComponentAddress,
+        delegate: ResourceOrNonFungible,
+        borrower_id: NonFungibleLocalId,
+        collateral_buckets: Vec<String>,
+    ) -> Self {
+        self.call_method_with_name_lookup(component, "delegate_add_collateral", |lookup| {
+            let buckets: Vec<ManifestBucket> = collateral_buckets.iter().map(|name| lookup.bucket(name)).collect();
+            (delegate, borrower_id, buckets)
+        })
+    }
+
+    fn radish_delegate_rebalance(
+        self,
+        component: ComponentAddress,
+        delegate: ResourceOrNonFungible,
+        from: NonFungibleLocalId,
+        to: NonFungibleLocalId,
+        asset: ResourceAddress,
+        amount: Decimal,
+    ) -> Self {
+        self.call_method(component, "delegate_rebalance", manifest_args!(delegate, from, to, asset, amount))
+    }
+
+    fn radish_preview_supply(self, component: ComponentAddress, collateral: AddrToAmount) -> Self {
+        self.call_method(component, "preview_supply", manifest_args!(collateral))
+    }
+
+    fn radish_preview_withdraw(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> Self {
+        self.call_method(component, "preview_withdraw", manifest_args!(borrower_id, repayment))
+    }
+
+    fn radish_preview_borrow_more(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, amount: Decimal) -> Self {
+        self.call_method(component, "preview_borrow_more", manifest_args!(borrower_id, amount))
+    }
+
+    fn radish_preview_add_collateral(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, collateral: AddrToAmount) -> Self {
+        self.call_method(component, "preview_add_collateral", manifest_args!(borrower_id, collateral))
+    }
+
+    fn radish_preview_withdraw_collateral(
+        self,
+        component: ComponentAddress,
+        borrower_id: NonFungibleLocalId,
+        asset: ResourceAddress,
+        amount: Decimal,
+    ) -> Self {
+        self.call_method(component, "preview_withdraw_collateral", manifest_args!(borrower_id, asset, amount))
+    }
+
+    fn radish_preview_repay_targeted(
+        self,
+        component: ComponentAddress,
+        borrower_id: NonFungibleLocalId,
+        repayment: Decimal,
+        asset: ResourceAddress,
+    ) -> Self {
+        self.call_method(component, "preview_repay_targeted", manifest_args!(borrower_id, repayment, asset))
+    }
+
+    fn radish_preview_repay_for(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> Self {
+        self.call_method(component, "preview_repay_for", manifest_args!(borrower_id, repayment))
+    }
+
+    fn radish_preview_liquidate(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> Self {
+        self.call_method(component, "preview_liquidate", manifest_args!(borrower_id, repayment))
+    }
+
+    fn radish_preview_redeem(self, component: ComponentAddress, redemption: Decimal) -> Self {
+        self.call_method(component, "preview_redeem", manifest_args!(redemption))
+    }
+
+    fn radish_preview_merge_positions(self, component: ComponentAddress, borrower_ids: Vec<NonFungibleLocalId>) -> Self {
+        self.call_method(component, "preview_merge_positions", manifest_args!(borrower_ids))
+    }
+
+    fn radish_preview_split_position(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, fraction: Decimal) -> Self {
+        self.call_method(component, "preview_split_position", manifest_args!(borrower_id, fraction))
+    }
+
+    fn radish_preview_rebalance(
+        self,
+        component: ComponentAddress,
+        from: NonFungibleLocalId,
+        to: NonFungibleLocalId,
+        asset: ResourceAddress,
+        amount: Decimal,
+    ) -> Self {
+        self.call_method(component, "preview_rebalance", manifest_args!(from, to, asset, amount))
+    }
+
+    fn radish_get_protocol_stats(self, component: ComponentAddress) -> Self {
+        self.call_method(component, "get_protocol_stats", manifest_args!())
+    }
+
+    fn radish_list_assets(self, component: ComponentAddress, start: Decimal, limit: u32) -> Self {
+        self.call_method(component, "list_assets", manifest_args!(start, limit))
+    }
+
+    fn radish_get_health_many(self, component: ComponentAddress, borrower_ids: Vec<NonFungibleLocalId>) -> Self {
+        self.call_method(component, "get_health_many", manifest_args!(borrower_ids))
+    }
+
+    fn radish_get_delegates(self, component: ComponentAddress, borrower_id: NonFungibleLocalId) -> Self {
+        self.call_method(component, "get_delegates", manifest_args!(borrower_id))
+    }
+
+    fn radish_refresh_position(self, component: ComponentAddress, borrower_id: NonFungibleLocalId) -> Self {
+        self.call_method(component, "refresh_position", manifest_args!(borrower_id))
+    }
+
+    fn radish_get_position_history(self, component: ComponentAddress, borrower_id: NonFungibleLocalId, cursor: u64, limit: u32) -> Self {
+        self.call_method(component, "get_position_history", manifest_args!(borrower_id, cursor, limit))
+    }
+
+    fn radish_get_riskiest_positions(self, component: ComponentAddress, limit: u32) -> Self {
+        self.call_method(component, "get_riskiest_positions", manifest_args!(limit))
+    }
+
+    fn radish_get_pool_deposit(self, component: ComponentAddress, depositor_id: NonFungibleLocalId) -> Self {
+        self.call_method(component, "get_pool_deposit", manifest_args!(depositor_id))
+    }
+}
+
+/* ------------ Complete Manifests ------------ */
+/// Addresses of a deployed Radish component, used to build complete transaction manifests
+///
+/// Every manifest locks `fee` from the given account and deposits everything left on the worktop back into it
+#[derive(Debug, Clone)]
+pub struct RadishManifests {
+    pub component: ComponentAddress,
+    pub radish: ResourceAddress,
+    pub borrower_badge: ResourceAddress,
+    pub depositor_badge: ResourceAddress,
+    pub admin_badge: ResourceAddress,
+    pub fee: Decimal,
+}
+
+impl RadishManifests {
+    pub fn new(
+        component: ComponentAddress,
+        radish: ResourceAddress,
+        borrower_badge: ResourceAddress,
+        depositor_badge: ResourceAddress,
+        admin_badge: ResourceAddress,
+    ) -> Self {
+        Self {
+            component,
+            radish,
+            borrower_badge,
+            depositor_badge,
+            admin_badge,
+            fee: DEFAULT_FEE,
+        }
+    }
+
+    pub fn instantiate(package: PackageAddress, account: ComponentAddress) -> TransactionManifestV1 {
+        ManifestBuilder::new()
+            .lock_fee(account, DEFAULT_FEE)
+            .radish_instantiate(package)
+            .deposit_batch(account)
+            .build()
+    }
+
+    /* ------------------- Admin ------------------ */
+    /// Mints admin badges using the owner badge held by the account
+    pub fn mint_admin_badges(&self, account: ComponentAddress, owner_badge: ResourceAddress, amount: Decimal) -> TransactionManifestV1 {
+        self.base(account)
+            .create_proof_from_account_of_amount(account, owner_badge, Decimal::ONE)
+            .mint_fungible(self.admin_badge, amount)
+            .deposit_batch(account)
+            .build()
+    }
+
+    pub fn add_asset(&self, account: ComponentAddress, asset: ResourceAddress, risk: RiskConfig) -> TransactionManifestV1 {
+        self.admin(account).radish_add_asset(self.component, asset, risk).build()
+    }
+
+    pub fn remove_asset(&self, account: ComponentAddress, asset: ResourceAddress) -> TransactionManifestV1 {
+        self.admin(account).radish_remove_asset(self.component, asset).build()
+    }
+
+    pub fn update_asset(&self, account: ComponentAddress, asset: ResourceAddress, status: AssetStatus, risk: RiskConfig) -> TransactionManifestV1 {
+        self.admin(account).radish_update_asset(self.component, asset, status, risk).build()
+    }
+
+    pub fn set_price(&self, account: ComponentAddress, asset: ResourceAddress, price: Decimal) -> TransactionManifestV1 {
+        self.admin(account).radish_set_price(self.component, asset, price).build()
+    }
+
+    pub fn set_paused(&self, account: ComponentAddress, paused: bool) -> TransactionManifestV1 {
+        self.admin(account).radish_set_paused(self.component, paused).build()
+    }
+
+    pub fn set_parameters(&self, account: ComponentAddress, parameters: ProtocolParameters) -> TransactionManifestV1 {
+        self.admin(account).radish_set_parameters(self.component, parameters).build()
+    }
+
+    /* ------------------ Lending ----------------- */
+    pub fn accrue_interest(&self, account: ComponentAddress) -> TransactionManifestV1 {
+        self.base(account).radish_accrue_interest(self.component).build()
+    }
+
+    pub fn estimate_loan(&self, account: ComponentAddress, collateral: AddrToAmount) -> TransactionManifestV1 {
+        self.base(account).radish_estimate_loan(self.component, collateral).build()
+    }
+
+    pub fn get_loan(&self, account: ComponentAddress, collateral: &AddrToAmount) -> TransactionManifestV1 {
+        let (builder, names) = Self::take_collateral(self.base(account), account, collateral);
+        builder.radish_get_loan(self.component, names).deposit_batch(account).build()
+    }
+
+    pub fn add_collateral(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId, collateral: &AddrToAmount) -> TransactionManifestV1 {
+        let (builder, names) = Self::take_collateral(self.position(account, borrower_id), account, collateral);
+        builder.radish_add_collateral(self.component, "position", names).build()
+    }
+
+    pub fn borrow_more(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId, amount: Decimal) -> TransactionManifestV1 {
+        self.position(account, borrower_id)
+            .radish_borrow_more(self.component, "position", amount)
+            .deposit_batch(account)
+            .build()
+    }
+
+    pub fn withdraw_collateral(
+        &self,
+        account: ComponentAddress,
+        borrower_id: NonFungibleLocalId,
+        asset: ResourceAddress,
+        amount: Decimal,
+    ) -> TransactionManifestV1 {
+        self.position(account, borrower_id)
+            .radish_withdraw_collateral(self.component, "position", asset, amount)
+            .deposit_batch(account)
+            .build()
+    }
+
+    pub fn repay_targeted(
+        &self,
+        account: ComponentAddress,
+        borrower_id: NonFungibleLocalId,
+        repayment: Decimal,
+        asset: ResourceAddress,
+    ) -> TransactionManifestV1 {
+        self.position(account, borrower_id)
+            .withdraw_from_account(account, self.radish, repayment)
+            .take_from_worktop(self.radish, repayment, "repayment")
+            .radish_repay_targeted(self.component, "position", "repayment", asset)
+            .deposit_batch(account)
+            .build()
+    }
+
+    pub fn estimate_repay(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> TransactionManifestV1 {
+        self.base(account)
+            .radish_estimate_repay(self.component, borrower_id, repayment)
+            .build()
+    }
+
+    pub fn repay_loan(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> TransactionManifestV1 {
+        self.position(account, borrower_id)
+            .withdraw_from_account(account, self.radish, repayment)
+            .take_from_worktop(self.radish, repayment, "repayment")
+            .radish_repay_loan(self.component, "position", "repayment")
+            .deposit_batch(account)
+            .build()
+    }
+
+    /// Repays several positions held by the account from one RSH withdrawal, worst health factor first
+    pub fn repay_many(&self, account: ComponentAddress, repayments: &[(NonFungibleLocalId, Decimal)]) -> TransactionManifestV1 {
+        let total: Decimal = repayments
+            .iter()
+            .fold(Decimal::ZERO, |total, (_, amount)| total.checked_add(*amount).unwrap());
+        let ids: Vec<NonFungibleLocalId> = repayments.iter().map(|(id, _)| id.clone()).collect();
+
+        self.base(account)
+            .create_proof_from_account_of_non_fungibles(account, self.borrower_badge, ids)
+            .pop_from_auth_zone("positions")
+            .withdraw_from_account(account, self.radish, total)
+            .take_from_worktop(self.radish, total, "repayment")
+            .radish_repay_many(self.component, "positions", repayments.to_vec(), "repayment")
+            .deposit_batch(account)
+            .build()
+    }
+
+    /// Repays the debt of any position from the account, without needing its badge
+    pub fn repay_for(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> TransactionManifestV1 {
+        self.base(account)
+            .withdraw_from_account(account, self.radish, repayment)
+            .take_from_worktop(self.radish, repayment, "repayment")
+            .radish_repay_for(self.component, borrower_id, "repayment")
+            .deposit_batch(account)
+            .build()
+    }
+
+    /// Merges the positions into the first one
+    pub fn merge_positions(&self, account: ComponentAddress, borrower_ids: Vec<NonFungibleLocalId>) -> TransactionManifestV1 {
+        self.base(account)
+            .create_proof_from_account_of_non_fungibles(account, self.borrower_badge, borrower_ids)
+            .pop_from_auth_zone("positions")
+            .radish_merge_positions(self.component, "positions")
+            .build()
+    }
+
+    pub fn split_position(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId, fraction: Decimal) -> TransactionManifestV1 {
+        self.position(account, borrower_id)
+            .radish_split_position(self.component, "position", fraction)
+            .deposit_batch(account)
+            .build()
+    }
+
+    /// Burns the badge of an emptied position
+    pub fn close_position(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId) -> TransactionManifestV1 {
+        self.base(account)
+            .withdraw_non_fungibles_from_account(account, self.borrower_badge, [borrower_id.clone()])
+            .take_non_fungibles_from_worktop(self.borrower_badge, [borrower_id], "borrower")
+            .radish_close_position(self.component, "borrower")
+            .deposit_batch(account)
+            .build()
+    }
+
+    pub fn liquidate(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> TransactionManifestV1 {
+        self.base(account)
+            .withdraw_from_account(account, self.radish, repayment)
+            .take_from_worktop(self.radish, repayment, "repayment")
+            .radish_liquidate(self.component, borrower_id, "repayment")
+            .deposit_batch(account)
+            .build()
+    }
+
+    /// Redeems RSH for collateral from the least healthy positions, depositing the collateral and any unused RSH
+    pub fn redeem(&self, account: ComponentAddress, redemption: Decimal) -> TransactionManifestV1 {
+        self.base(account)
+            .withdraw_from_account(account, self.radish, redemption)
+            .take_from_worktop(self.radish, redemption, "redemption")
+            .radish_redeem(self.component, "redemption")
+            .deposit_batch(account)
+            .build()
+    }
+
+    /* ----------------- Delegation ---------------- */
+    /// Grants the delegate the permissions on a position held by the account, empty permissions revoking them. The grant
+    /// lapses once the account no longer holds the position
+    pub fn set_delegate(
+        &self,
+        account: ComponentAddress,
+        borrower_id: NonFungibleLocalId,
+        delegate: ResourceOrNonFungible,
+        permissions: DelegatePermissions,
+    ) -> TransactionManifestV1 {
+        self.position(account, borrower_id)
+            .radish_set_delegate(self.component, "position", account, delegate, permissions)
+            .build()
+    }
+
+    pub fn delegate_add_collateral(
+        &self,
+        account: ComponentAddress,
+        delegate: ResourceOrNonFungible,
+        borrower_id: NonFungibleLocalId,
+        collateral: &AddrToAmount,
+    ) -> TransactionManifestV1 {
+        let (builder, names) = Self::take_collateral(Self::delegate(self.base(account), account, &delegate), account, collateral);
+        builder
+            .radish_delegate_add_collateral(self.component, delegate, borrower_id, names)
+            .build()
+    }
+
+    pub fn delegate_rebalance(
+        &self,
+        account: ComponentAddress,
+        delegate: ResourceOrNonFungible,
+        from: NonFungibleLocalId,
+        to: NonFungibleLocalId,
+        asset: ResourceAddress,
+        amount: Decimal,
+    ) -> TransactionManifestV1 {
+        Self::delegate(self.base(account), account, &delegate)
+            .radish_delegate_rebalance(self.component, delegate, from, to, asset, amount)
+            .build()
+    }
+
+    /* --------------- Stability Pool -------------- */
+    /// Deposits RSH into the stability pool, depositing the new depositor badge into the account
+    pub fn deposit_to_pool(&self, account: ComponentAddress, amount: Decimal) -> TransactionManifestV1 {
+        self.base(account)
+            .withdraw_from_account(account, self.radish, amount)
+            .take_from_worktop(self.radish, amount, "deposit")
+            .radish_deposit_to_pool(self.component, "deposit")
+            .deposit_batch(account)
+            .build()
+    }
+
+    /// Withdraws up to `amount` RSH and all collateral gains of a deposit held by the account
+    pub fn withdraw_from_pool(&self, account: ComponentAddress, depositor_id: NonFungibleLocalId, amount: Decimal) -> TransactionManifestV1 {
+        self.base(account)
+            .create_proof_from_account_of_non_fungibles(account, self.depositor_badge, [depositor_id])
+            .pop_from_auth_zone("depositor")
+            .radish_withdraw_from_pool(self.component, "depositor", amount)
+            .deposit_batch(account)
+            .build()
+    }
+
+    pub fn liquidate_with_pool(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId) -> TransactionManifestV1 {
+        self.base(account)
+            .radish_liquidate_with_pool(self.component, borrower_id)
+            .build()
+    }
+
+    /* ----------------- Previews ----------------- */
+    pub fn preview_supply(&self, account: ComponentAddress, collateral: AddrToAmount) -> TransactionManifestV1 {
+        self.base(account).radish_preview_supply(self.component, collateral).build()
+    }
+
+    pub fn preview_withdraw(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> TransactionManifestV1 {
+        self.base(account)
+            .radish_preview_withdraw(self.component, borrower_id, repayment)
+            .build()
+    }
+
+    pub fn preview_borrow_more(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId, amount: Decimal) -> TransactionManifestV1 {
+        self.base(account)
+            .radish_preview_borrow_more(self.component, borrower_id, amount)
+            .build()
+    }
+
+    pub fn preview_add_collateral(
+        &self,
+        account: ComponentAddress,
+        borrower_id: NonFungibleLocalId,
+        collateral: AddrToAmount,
+    ) -> TransactionManifestV1 {
+        self.base(account)
+            .radish_preview_add_collateral(self.component, borrower_id, collateral)
+            .build()
+    }
+
+    pub fn preview_withdraw_collateral(
+        &self,
+        account: ComponentAddress,
+        borrower_id: NonFungibleLocalId,
+        asset: ResourceAddress,
+        amount: Decimal,
+    ) -> TransactionManifestV1 {
+        self.base(account)
+            .radish_preview_withdraw_collateral(self.component, borrower_id, asset, amount)
+            .build()
+    }
+
+    pub fn preview_repay_targeted(
+        &self,
+        account: ComponentAddress,
+        borrower_id: NonFungibleLocalId,
+        repayment: Decimal,
+        asset: ResourceAddress,
+    ) -> TransactionManifestV1 {
+        self.base(account)
+            .radish_preview_repay_targeted(self.component, borrower_id, repayment, asset)
+            .build()
+    }
+
+    pub fn preview_repay_for(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> TransactionManifestV1 {
+        self.base(account)
+            .radish_preview_repay_for(self.component, borrower_id, repayment)
+            .build()
+    }
+
+    pub fn preview_liquidate(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId, repayment: Decimal) -> TransactionManifestV1 {
+        self.base(account)
+            .radish_preview_liquidate(self.component, borrower_id, repayment)
+            .build()
+    }
+
+    pub fn preview_redeem(&self, account: ComponentAddress, redemption: Decimal) -> TransactionManifestV1 {
+        self.base(account).radish_preview_redeem(self.component, redemption).build()
+    }
+
+    pub fn preview_merge_positions(&self, account: ComponentAddress, borrower_ids: Vec<NonFungibleLocalId>) -> TransactionManifestV1 {
+        self.base(account)
+            .radish_preview_merge_positions(self.component, borrower_ids)
+            .build()
+    }
+
+    pub fn preview_split_position(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId, fraction: Decimal) -> TransactionManifestV1 {
+        self.base(account)
+            .radish_preview_split_position(self.component, borrower_id, fraction)
+            .build()
+    }
+
+    pub fn preview_rebalance(
+        &self,
+        account: ComponentAddress,
+        from: NonFungibleLocalId,
+        to: NonFungibleLocalId,
+        asset: ResourceAddress,
+        amount: Decimal,
+    ) -> TransactionManifestV1 {
+        self.base(account)
+            .radish_preview_rebalance(self.component, from, to, asset, amount)
+            .build()
+    }
+
+    /* ------------------- Read ------------------- */
+    pub fn get_protocol_stats(&self, account: ComponentAddress) -> TransactionManifestV1 {
+        self.base(account).radish_get_protocol_stats(self.component).build()
+    }
+
+    pub fn list_assets(&self, account: ComponentAddress, start: Decimal, limit: u32) -> TransactionManifestV1 {
+        self.base(account).radish_list_assets(self.component, start, limit).build()
+    }
+
+    pub fn get_health_many(&self, account: ComponentAddress, borrower_ids: Vec<NonFungibleLocalId>) -> TransactionManifestV1 {
+        self.base(account).radish_get_health_many(self.component, borrower_ids).build()
+    }
+
+    pub fn get_delegates(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId) -> TransactionManifestV1 {
+        self.base(account).radish_get_delegates(self.component, borrower_id).build()
+    }
+
+    pub fn refresh_position(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId) -> TransactionManifestV1 {
+        self.base(account).radish_refresh_position(self.component, borrower_id).build()
+    }
+
+    pub fn get_position_history(
+        &self,
+        account: ComponentAddress,
+        borrower_id: NonFungibleLocalId,
+        cursor: u64,
+        limit: u32,
+    ) -> TransactionManifestV1 {
+        self.base(account)
+            .radish_get_position_history(self.component, borrower_id, cursor, limit)
+            .build()
+    }
+
+    /// Least healthy indebted positions, decoded with `decode_health_many`
+    pub fn get_riskiest_positions(&self, account: ComponentAddress, limit: u32) -> TransactionManifestV1 {
+        self.base(account).radish_get_riskiest_positions(self.component, limit).build()
+    }
+
+    pub fn get_pool_deposit(&self, account: ComponentAddress, depositor_id: NonFungibleLocalId) -> TransactionManifestV1 {
+        self.base(account).radish_get_pool_deposit(self.component, depositor_id).build()
+    }
+
+    /* ------------------ Helpers ----------------- */
+    fn base(&self, account: ComponentAddress) -> ManifestBuilder {
+        ManifestBuilder::new().lock_fee(account, self.fee)
+    }
+
+    /// Base manifest with a proof of the position named "position"
+    fn position(&self, account: ComponentAddress, borrower_id: NonFungibleLocalId) -> ManifestBuilder {
+        self.base(account)
+            .create_proof_from_account_of_non_fungibles(account, self.borrower_badge, [borrower_id])
+            .pop_from_auth_zone("position")
+    }
+
+    /// Puts a proof of the delegate badge held by the account into the auth zone; virtual badges, e.g. the signature badge
+    /// of the transaction signer, are already there
+    fn delegate(builder: ManifestBuilder, account: ComponentAddress, delegate: &ResourceOrNonFungible) -> ManifestBuilder {
+        match delegate {
+            ResourceOrNonFungible::Resource(resource) => builder.create_proof_from_account_of_amount(account, *resource, Decimal::ONE),
+            ResourceOrNonFungible::NonFungible(global_id) => {
+                let resource: ResourceAddress = global_id.resource_address();
+                if [SECP256K1_SIGNATURE_RESOURCE, ED25519_SIGNATURE_RESOURCE, GLOBAL_CALLER_RESOURCE].contains(&resource) {
+                    builder
+                } else {
+                    builder.create_proof_from_account_of_non_fungibles(account, resource, [global_id.local_id().clone()])
+                }
+            }
+        }
+    }
+
+    /// Withdraws the collateral from the account into buckets, returning their names
+    fn take_collateral(mut builder: ManifestBuilder, account: ComponentAddress, collateral: &AddrToAmount) -> (ManifestBuilder, Vec<String>) {
+        let mut names: Vec<String> = Vec::new();
+        for (i, (&address, &amount)) in collateral.iter().enumerate() {
+            let name: String = format!("collateral_{}", i);
+            builder = builder
+                .withdraw_from_account(account, address, amount)
+                .take_from_worktop(address, amount, name.as_str());
+            names.push(name);
+        }
+        (builder, names)
+    }
+
+    fn admin(&self, account: ComponentAddress) -> ManifestBuilder {
+        self.base(account)
+            .create_proof_from_account_of_amount(account, self.admin_badge, Decimal::ONE)
+    }
+}
+
+/* ---------------- Mock Tokens --------------- */
+/// Manifests for the `MockToken` faucet blueprint, published in the same package as Radish
+pub struct MockTokenManifests;
+
+impl MockTokenManifests {
+    pub fn instantiate(
+        package: PackageAddress,
+        account: ComponentAddress,
+        name: &str,
+        symbol: &str,
+        divisibility: u8,
+        allowance: Decimal,
+        window: i64,
+    ) -> TransactionManifestV1 {
+        ManifestBuilder::new()
+            .lock_fee(account, DEFAULT_FEE)
+            .call_function(
+                package,
+                MOCK_TOKEN_BLUEPRINT_NAME,
+                "instantiate",
+                manifest_args!(name.to_string(), symbol.to_string(), divisibility, allowance, window),
+            )
+            .build()
+    }
+
+    pub fn free_tokens(faucet: ComponentAddress, account: ComponentAddress, amount: Decimal) -> TransactionManifestV1 {
+        ManifestBuilder::new()
+            .lock_fee(account, DEFAULT_FEE)
+            .call_method(faucet, "free_tokens", manifest_args!(amount))
+            .deposit_batch(account)
+            .build()
+    }
+}
@@ -0,0 +1,263 @@
+/* ------------------ Imports ----------------- */
+use crate::math;
+use crate::shared::HealthTier;
+use scrypto::prelude::*;
+
+/* ----------------- Constants ---------------- */
+/// Positions at least this healthy are shown as safe
+pub const SAFE_HEALTH_FACTOR: Decimal = dec!(2);
+/// Decimal places shown for amounts and health factors
+pub const DISPLAY_DECIMALS: i32 = 2;
+
+/* ------------------- Tiers ------------------ */
+/// Health tier of a position, `at_risk_below` being the health factor under which it is at risk of liquidation
+pub fn health_tier(health_factor: Decimal, debt: Decimal, at_risk_below: Decimal) -> HealthTier {
+    if debt == Decimal::ZERO {
+        HealthTier::DebtFree
+    } else if health_factor >= SAFE_HEALTH_FACTOR {
+        HealthTier::Safe
+    } else if health_factor >= at_risk_below {
+        HealthTier::Moderate
+    } else if health_factor >= Decimal::ONE {
+        HealthTier::AtRisk
+    } else {
+        HealthTier::Liquidatable
+    }
+}
+
+/* ----------------- Rendering ---------------- */
+/// A collateral asset of a position, as shown in wallets
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DisplayAsset {
+    pub symbol: String,
+    pub amount: Decimal,
+    /// USD value of the amount
+    pub value: Decimal,
+}
+
+/// One line summary of a position, e.g. `Radish position backed by 1000 XRD, owing 500 RSH. Health factor 1.3 (Moderate)`
+pub fn description(collateral: &[DisplayAsset], debt: Decimal, health_factor: Decimal, tier: HealthTier) -> String {
+    if collateral.is_empty() && debt == Decimal::ZERO {
+        return "Empty Radish position".to_string();
+    }
+
+    let backing: Vec<String> = collateral
+        .iter()
+        .map(|asset| format!("{} {}", format_amount(asset.amount), asset.symbol))
+        .collect();
+    let health: String = match tier {
+        HealthTier::DebtFree => tier.label().to_string(),
+        _ => format!("Health factor {} ({})", format_amount(health_factor), tier.label()),
+    };
+    format!("Radish position backed by {}, owing {} RSH. {}", backing.join(", "), format_amount(debt), health)
+}
+
+/// SVG image of a position as a data URL, so wallets can show it without an image server
+pub fn image_url(collateral: &[DisplayAsset], debt: Decimal, health_factor: Decimal, tier: HealthTier) -> Url {
+    Url::of(format!("data:image/svg+xml,{}", percent_encode(&svg(collateral, debt, health_factor, tier))))
+}
+
+/// Fill colour of a health tier, from green to red
+pub fn tier_colour(tier: HealthTier) -> &'static str {
+    match tier {
+        HealthTier::DebtFree => "#a8a29e",
+        HealthTier::Safe => "#22c55e",
+        HealthTier::Moderate => "#eab308",
+        HealthTier::AtRisk => "#f97316",
+        HealthTier::Liquidatable => "#ef4444",
+    }
+}
+
+/// Rounds towards zero to the displayed decimal places, e.g. `1.2999` is shown as `1.29`
+pub fn format_amount(amount: Decimal) -> String {
+    amount.checked_round(DISPLAY_DECIMALS, RoundingMode::ToZero).unwrap().to_string()
+}
+
+/* -------------------- SVG ------------------- */
+// A 400 x 400 card: tier pill, debt and collateral value, a health gauge, then one bar per collateral asset showing its
+// share of the collateral value, largest first
+
+/// Width of the gauge and the collateral bars
+const BAR_WIDTH: Decimal = dec!(352);
+/// Health factor at which the gauge is full
+const GAUGE_HEALTH_FACTOR: Decimal = dec!(3);
+/// Collateral assets given a bar, the rest are counted as "+N more"
+const MAX_BARS: usize = 5;
+
+fn svg(collateral: &[DisplayAsset], debt: Decimal, health_factor: Decimal, tier: HealthTier) -> String {
+    let colour: &str = tier_colour(tier);
+    let total_value: Decimal = collateral
+        .iter()
+        .fold(Decimal::ZERO, |total, asset| total.checked_add(asset.value).unwrap());
+
+    let mut svg: String = String::from(
+        "<svg xmlns='http://www.w3.org/2000/svg' width='400' height='400' viewBox='0 0 400 400' font-family='sans-serif'>\
+         <rect width='400' height='400' rx='24' fill='#1c1917'/>\
+         <text x='24' y='44' font-size='22' fill='#fafaf9'>Radish Position</text>",
+    );
+    svg.push_str(&format!(
+        "<rect x='256' y='22' width='120' height='30' rx='15' fill='{}'/>\
+         <text x='316' y='43' font-size='14' text-anchor='middle' fill='#1c1917'>{}</text>",
+        colour,
+        tier.label()
+    ));
+
+    // Debt and collateral value
+    svg.push_str(&format!(
+        "<text x='24' y='84' font-size='12' fill='#a8a29e'>Debt</text>\
+         <text x='24' y='106' font-size='20' fill='#fafaf9'>{} RSH</text>\
+         <text x='376' y='84' font-size='12' text-anchor='end' fill='#a8a29e'>Collateral value</text>\
+         <text x='376' y='106' font-size='20' text-anchor='end' fill='#fafaf9'>${}</text>",
+        escape_xml(&format_amount(debt)),
+        escape_xml(&format_amount(total_value))
+    ));
+
+    // Health gauge, full for debt free positions
+    let gauge: Decimal = match tier {
+        HealthTier::DebtFree => BAR_WIDTH,
+        _ => math::mul_div(BAR_WIDTH, health_factor.min(GAUGE_HEALTH_FACTOR), GAUGE_HEALTH_FACTOR, math::ROUND_DOWN),
+    };
+    let health: String = match tier {
+        HealthTier::DebtFree => "No debt".to_string(),
+        _ => format!("Health factor {}", format_amount(health_factor)),
+    };
+    svg.push_str(&format!(
+        "<rect x='24' y='122' width='352' height='12' rx='6' fill='#44403c'/>\
+         <rect x='24' y='122' width='{}' height='12' rx='6' fill='{}'/>\
+         <text x='24' y='154' font-size='14' fill='{}'>{}</text>",
+        format_pixels(gauge),
+        colour,
+        colour,
+        health
+    ));
+
+    // Collateral breakdown
+    let mut assets: Vec<&DisplayAsset> = collateral.iter().collect();
+    assets.sort_by(|a, b| b.value.cmp(&a.value));
+    svg.push_str("<text x='24' y='190' font-size='12' fill='#a8a29e'>Collateral</text>");
+    if assets.len() > MAX_BARS {
+        svg.push_str(&format!(
+            "<text x='376' y='190' font-size='12' text-anchor='end' fill='#a8a29e'>+{} more</text>",
+            assets.len() - MAX_BARS
+        ));
+    }
+    for (i, asset) in assets.iter().take(MAX_BARS).enumerate() {
+        let share: Decimal = if total_value == Decimal::ZERO {
+            Decimal::ZERO
+        } else {
+            math::mul_div(asset.value, Decimal::ONE, total_value, math::ROUND_DOWN)
+        };
+        let y: usize = 212 + 38 * i;
+        svg.push_str(&format!(
+            "<text x='24' y='{}' font-size='14' fill='#fafaf9'>{} {}</text>\
+             <text x='376' y='{}' font-size='14' text-anchor='end' fill='#a8a29e'>{}%</text>\
+             <rect x='24' y='{}' width='352' height='8' rx='4' fill='#44403c'/>\
+             <rect x='24' y='{}' width='{}' height='8' rx='4' fill='#e7e5e4'/>",
+            y,
+            escape_xml(&format_amount(asset.amount)),
+            escape_xml(&asset.symbol),
+            y,
+            format_pixels(share.checked_mul(Decimal::ONE_HUNDRED).unwrap()),
+            y + 8,
+            y + 8,
+            format_pixels(math::mul_div(BAR_WIDTH, share, Decimal::ONE, math::ROUND_DOWN))
+        ));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Whole pixels, or percents, rounded down
+fn format_pixels(amount: Decimal) -> String {
+    amount.checked_round(0, RoundingMode::ToZero).unwrap().to_string()
+}
+
+/* ------------------ Encoding ---------------- */
+/// Escapes text placed inside SVG elements; symbols come from resource metadata and are not trusted
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\'', "&apos;")
+        .replace('"', "&quot;")
+}
+
+/// Percent-encodes everything but unreserved characters and those harmless in a data URL
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'\'' | b'/' | b':' | b'=' | b',' | b'(' | b')' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/* ------------------- Tests ------------------ */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_tier() {
+        assert_eq!(health_tier(Decimal::MAX, dec!(0), dec!("1.25")), HealthTier::DebtFree);
+        assert_eq!(health_tier(dec!(2), dec!(1), dec!("1.25")), HealthTier::Safe);
+        assert_eq!(health_tier(dec!("1.3"), dec!(1), dec!("1.25")), HealthTier::Moderate);
+        assert_eq!(health_tier(dec!("1.1"), dec!(1), dec!("1.25")), HealthTier::AtRisk);
+        assert_eq!(health_tier(dec!("0.99"), dec!(1), dec!("1.25")), HealthTier::Liquidatable);
+    }
+
+    fn asset(symbol: &str, amount: Decimal, value: Decimal) -> DisplayAsset {
+        DisplayAsset {
+            symbol: symbol.to_string(),
+            amount,
+            value,
+        }
+    }
+
+    #[test]
+    fn test_description() {
+        let collateral: Vec<DisplayAsset> = vec![asset("XRD", dec!(1000), dec!(1000)), asset("BTC", dec!("0.123456"), dec!(100))];
+        assert_eq!(
+            description(&collateral, dec!(500), dec!("1.2999"), HealthTier::AtRisk),
+            "Radish position backed by 1000 XRD, 0.12 BTC, owing 500 RSH. Health factor 1.29 (At risk)"
+        );
+        assert_eq!(description(&[], dec!(0), Decimal::MAX, HealthTier::DebtFree), "Empty Radish position");
+    }
+
+    #[test]
+    fn test_svg_bars_and_gauge() {
+        let collateral: Vec<DisplayAsset> = vec![asset("BTC", dec!(1), dec!(250)), asset("XRD", dec!(1000), dec!(750))];
+        let svg: String = svg(&collateral, dec!(500), dec!("1.5"), HealthTier::Moderate);
+
+        // Half of the full gauge at 1.5, coloured by tier
+        assert!(svg.contains("width='176' height='12' rx='6' fill='#eab308'"));
+        // Largest share first
+        assert!(svg.find("XRD").unwrap() < svg.find("BTC").unwrap());
+        assert!(svg.contains(">75%<") && svg.contains("width='264' height='8'"));
+        assert!(svg.contains(">25%<") && svg.contains("width='88' height='8'"));
+        assert!(svg.contains(">$1000<"));
+    }
+
+    #[test]
+    fn test_svg_counts_assets_without_bars() {
+        let collateral: Vec<DisplayAsset> = (0..7).map(|i| asset(&format!("T{}", i), dec!(1), Decimal::from(i + 1))).collect();
+        let svg: String = svg(&collateral, dec!(0), Decimal::MAX, HealthTier::DebtFree);
+
+        assert!(svg.contains(">+2 more<"));
+        assert!(!svg.contains(">1 T0<") && !svg.contains(">1 T1<"));
+        assert!(svg.contains("width='352' height='12' rx='6' fill='#a8a29e'"));
+    }
+
+    #[test]
+    fn test_image_url_is_encoded() {
+        let collateral: Vec<DisplayAsset> = vec![asset("<A&B>", dec!(1), dec!(1))];
+        let url: String = image_url(&collateral, dec!(1), dec!(2), HealthTier::Safe).as_str().to_string();
+
+        assert!(url.starts_with("data:image/svg+xml,%3Csvg"));
+        assert!(url.contains("%26lt%3BA%26amp%3BB%26gt%3B"));
+        assert!(!url.contains(' ') && !url.contains('#') && !url.contains('<'));
+    }
+}
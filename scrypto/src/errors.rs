@@ -0,0 +1,132 @@
+/* ------------------ Imports ----------------- */
+// Usages
+use crate::shared::AddrToAmount;
+use scrypto::prelude::*;
+
+/* ------------------ Errors ------------------ */
+// Codes are stable: never renumber a variant, only add new ones. They are grouped by hundreds:
+// 1xx assets, 2xx configuration, 3xx collateral, 4xx positions, 5xx protocol state, 6xx stability pool
+
+/// Every failure raised by the Radish component
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq)]
+pub enum RadishError {
+    /* ------------------ Assets ------------------ */
+    AssetNotFungible { asset: ResourceAddress },
+    AssetAlreadyListed { asset: ResourceAddress },
+    AssetNotListed { asset: ResourceAddress },
+    AssetVaultNotEmpty { asset: ResourceAddress, amount: Decimal },
+    RadishNotConfigurable,
+
+    /* --------------- Configuration -------------- */
+    InvalidRiskConfig { field: String, value: Decimal },
+    InvalidParameter { field: String, value: Decimal },
+    InvalidPrice { asset: ResourceAddress, price: Decimal },
+    PriceUnavailable { asset: ResourceAddress },
+
+    /* ---------------- Collateral ---------------- */
+    NoCollateral,
+    RadishAsCollateral,
+    AssetFrozen { asset: ResourceAddress },
+    InvalidAmount { resource: ResourceAddress, amount: Decimal },
+    SupplyCapExceeded { asset: ResourceAddress, supplied: Decimal, supply_cap: Decimal },
+    CollateralWorthless,
+    InsufficientVault { asset: ResourceAddress, requested: Decimal, available: Decimal },
+
+    /* ----------------- Positions ---------------- */
+    PositionNotFound { position: NonFungibleLocalId },
+    InvalidBorrowerBadge { resource: ResourceAddress, amount: Decimal },
+    InvalidRepayment { resource: ResourceAddress, amount: Decimal },
+    PositionHealthy { health_factor: Decimal },
+    InsufficientCollateral { debt: Decimal, borrow_capacity: Decimal },
+    HealthBelowMargin { health_factor: Decimal, minimum: Decimal },
+    PositionNotEmpty { collateral: AddrToAmount, debt: Decimal },
+    PositionNotProven { position: NonFungibleLocalId },
+    InvalidFraction { fraction: Decimal },
+    DelegateNotAuthorized { position: NonFungibleLocalId, delegate: ResourceOrNonFungible },
+    SamePosition { position: NonFungibleLocalId },
+    PositionNotHeld { position: NonFungibleLocalId, holder: ComponentAddress },
+
+    /* -------------- Protocol State -------------- */
+    Paused,
+    DebtCeilingExceeded { total_debt: Decimal, debt_ceiling: Decimal },
+    NothingToRedeem,
+
+    /* --------------- Stability Pool -------------- */
+    PoolEmpty,
+    InvalidDepositorBadge { resource: ResourceAddress, amount: Decimal },
+}
+
+impl RadishError {
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::AssetNotFungible { .. } => 101,
+            Self::AssetAlreadyListed { .. } => 102,
+            Self::AssetNotListed { .. } => 103,
+            Self::AssetVaultNotEmpty { .. } => 104,
+            Self::RadishNotConfigurable => 105,
+
+            Self::InvalidRiskConfig { .. } => 201,
+            Self::InvalidParameter { .. } => 202,
+            Self::InvalidPrice { .. } => 203,
+            Self::PriceUnavailable { .. } => 204,
+
+            Self::NoCollateral => 301,
+            Self::RadishAsCollateral => 302,
+            Self::AssetFrozen { .. } => 303,
+            Self::InvalidAmount { .. } => 304,
+            Self::SupplyCapExceeded { .. } => 305,
+            Self::CollateralWorthless => 306,
+            Self::InsufficientVault { .. } => 307,
+
+            Self::PositionNotFound { .. } => 401,
+            Self::InvalidBorrowerBadge { .. } => 402,
+            Self::InvalidRepayment { .. } => 403,
+            Self::PositionHealthy { .. } => 404,
+            Self::InsufficientCollateral { .. } => 405,
+            Self::HealthBelowMargin { .. } => 406,
+            Self::PositionNotEmpty { .. } => 407,
+            Self::PositionNotProven { .. } => 408,
+            Self::InvalidFraction { .. } => 409,
+            Self::DelegateNotAuthorized { .. } => 410,
+            Self::SamePosition { .. } => 411,
+            Self::PositionNotHeld { .. } => 412,
+
+            Self::Paused => 501,
+            Self::DebtCeilingExceeded { .. } => 502,
+            Self::NothingToRedeem => 503,
+
+            Self::PoolEmpty => 601,
+            Self::InvalidDepositorBadge { .. } => 602,
+        }
+    }
+
+    /// Panic message carrying the code and the SBOR encoded error, `[RadishError <code>:<hex>] <description>`
+    ///
+    /// ! radish-sdk parses this format, keep both in sync
+    pub fn message(&self) -> String {
+        let payload: String = scrypto_encode(self)
+            .unwrap()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        format!("[{} {}:{}] {:?}", ERROR_TAG, self.code(), payload, self)
+    }
+
+    /// Aborts the transaction with this error
+    pub fn raise(self) -> ! {
+        panic!("{}", self.message())
+    }
+}
+
+/// Marks the start of an encoded error in a panic message
+pub const ERROR_TAG: &str = "RadishError";
+
+/// Raises the error unless the condition holds, only building the error on failure
+macro_rules! ensure {
+    ($condition:expr, $error:expr) => {
+        if !$condition {
+            $crate::errors::RadishError::raise($error);
+        }
+    };
+}
+pub(crate) use ensure;
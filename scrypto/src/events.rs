@@ -0,0 +1,193 @@
+/* ------------------ Imports ----------------- */
+// Usages
+use crate::shared::*;
+use scrypto::prelude::*;
+
+/* ------------------ Assets ------------------ */
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct AssetAddedEvent {
+    pub asset: ResourceAddress,
+    pub config: AssetConfig,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct AssetRemovedEvent {
+    pub asset: ResourceAddress,
+    pub id: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct AssetUpdatedEvent {
+    pub asset: ResourceAddress,
+    pub config: AssetConfig,
+}
+
+/* ----------------- Positions ---------------- */
+// All position events carry the resulting collateral and debt of the position, so an indexer can
+// rebuild each position from the event stream alone
+
+/// Collateral deposited into a position
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct SupplyEvent {
+    pub position: NonFungibleLocalId,
+    pub asset: ResourceAddress,
+    pub amount: Decimal,
+    pub collateral: AddrToAmount,
+    pub debt: Decimal,
+    pub total_supplied: Decimal,
+}
+
+/// Collateral released from a position
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct WithdrawEvent {
+    pub position: NonFungibleLocalId,
+    pub asset: ResourceAddress,
+    pub amount: Decimal,
+    pub collateral: AddrToAmount,
+    pub debt: Decimal,
+    pub total_supplied: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct BorrowEvent {
+    pub position: NonFungibleLocalId,
+    pub resource: ResourceAddress,
+    pub amount: Decimal,
+    pub collateral: AddrToAmount,
+    pub debt: Decimal,
+    pub total_debt: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct RepayEvent {
+    pub position: NonFungibleLocalId,
+    pub resource: ResourceAddress,
+    pub amount: Decimal,
+    pub collateral: AddrToAmount,
+    pub debt: Decimal,
+    pub total_debt: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct LiquidateEvent {
+    pub position: NonFungibleLocalId,
+    pub resource: ResourceAddress,
+    pub repaid: Decimal,
+    pub seized: AddrToAmount,
+    pub collateral: AddrToAmount,
+    pub debt: Decimal,
+    pub total_debt: Decimal,
+}
+
+/// Positions folded into `position`, each of the `merged` ones left empty
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct MergeEvent {
+    pub position: NonFungibleLocalId,
+    pub merged: Vec<NonFungibleLocalId>,
+    pub collateral: AddrToAmount,
+    pub debt: Decimal,
+}
+
+/// A `fraction` of `position` moved into the newly minted `new_position`
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct SplitEvent {
+    pub position: NonFungibleLocalId,
+    pub new_position: NonFungibleLocalId,
+    pub fraction: Decimal,
+    pub collateral: AddrToAmount,
+    pub debt: Decimal,
+    pub new_collateral: AddrToAmount,
+    pub new_debt: Decimal,
+}
+
+/// RSH redeemed for collateral drawn from the listed positions, lowest health factor first
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct RedemptionEvent {
+    pub redeemed: Decimal,
+    pub fee_rate: Decimal,
+    pub base_rate: Decimal,
+    pub released: AddrToAmount,
+    pub fees: AddrToAmount,
+    pub positions: Vec<NonFungibleLocalId>,
+    pub total_debt: Decimal,
+}
+
+/* --------------- Stability Pool -------------- */
+// Pool events carry the resulting RSH in the pool, `total_deposits`
+
+/// RSH deposited into the stability pool under a newly minted depositor badge
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct PoolDepositEvent {
+    pub depositor: NonFungibleLocalId,
+    pub amount: Decimal,
+    pub total_deposits: Decimal,
+}
+
+/// RSH and collateral gains withdrawn from a deposit, `deposit` being the RSH left in it
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct PoolWithdrawEvent {
+    pub depositor: NonFungibleLocalId,
+    pub amount: Decimal,
+    pub gains: AddrToAmount,
+    pub deposit: Decimal,
+    pub total_deposits: Decimal,
+}
+
+/// Debt of `position` absorbed by burning pooled RSH, its seized collateral shared among depositors
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct PoolAbsorbEvent {
+    pub position: NonFungibleLocalId,
+    pub absorbed: Decimal,
+    pub seized: AddrToAmount,
+    pub total_deposits: Decimal,
+    pub product: Decimal,
+    pub epoch: u64,
+    pub scale: u64,
+}
+
+/* ----------------- Delegation ---------------- */
+/// Permissions of `delegate` on `position` replaced, empty permissions revoking the delegation
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct DelegateEvent {
+    pub position: NonFungibleLocalId,
+    pub holder: ComponentAddress,
+    pub delegate: ResourceOrNonFungible,
+    pub permissions: DelegatePermissions,
+}
+
+/// Collateral moved by `delegate` from one position into another
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct RebalanceEvent {
+    pub delegate: ResourceOrNonFungible,
+    pub from: NonFungibleLocalId,
+    pub to: NonFungibleLocalId,
+    pub asset: ResourceAddress,
+    pub amount: Decimal,
+    pub from_collateral: AddrToAmount,
+    pub to_collateral: AddrToAmount,
+}
+
+/* ------------------ Protocol ----------------- */
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct InterestAccrualEvent {
+    pub interest_index: Decimal,
+    pub interest: Decimal,
+    pub total_debt: Decimal,
+    pub timestamp: i64,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct OracleUpdateEvent {
+    pub asset: ResourceAddress,
+    pub price: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct PauseToggleEvent {
+    pub paused: bool,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent, Debug, Clone)]
+pub struct ParameterChangeEvent {
+    pub parameters: ProtocolParameters,
+}
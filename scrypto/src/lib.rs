@@ -1,9 +1,46 @@
 /* ------------------ Imports ----------------- */
+// Modules
+pub mod display;
+pub mod errors;
+pub mod events;
+pub mod math;
+pub mod mock_token;
+pub mod shared;
+pub mod user;
+// Usages
+use crate::display::{self, DisplayAsset};
+use crate::errors::{ensure, RadishError};
+use crate::events::*;
+use crate::math;
+use crate::shared::*;
+use crate::user::{Borrower, Depositor};
 use scrypto::prelude::*;
 use scrypto_avltree::AvlTree;
 
 /* ----------------- Blueprint ---------------- */
 #[blueprint]
+#[events(
+    AssetAddedEvent,
+    AssetRemovedEvent,
+    AssetUpdatedEvent,
+    SupplyEvent,
+    WithdrawEvent,
+    BorrowEvent,
+    RepayEvent,
+    LiquidateEvent,
+    RedemptionEvent,
+    PoolDepositEvent,
+    PoolWithdrawEvent,
+    PoolAbsorbEvent,
+    MergeEvent,
+    SplitEvent,
+    DelegateEvent,
+    RebalanceEvent,
+    InterestAccrualEvent,
+    OracleUpdateEvent,
+    PauseToggleEvent,
+    ParameterChangeEvent
+)]
 mod radish {
     /* ------------ Role Authorization ------------ */
     enable_method_auth! {
@@ -14,6 +51,52 @@ mod radish {
         methods {
             add_asset => restrict_to: [admin];
             remove_asset => restrict_to: [admin];
+            update_asset => restrict_to: [admin];
+            set_price => restrict_to: [admin];
+            set_paused => restrict_to: [admin];
+            set_parameters => restrict_to: [admin];
+            accrue_interest => PUBLIC;
+            estimate_loan => PUBLIC;
+            get_loan => PUBLIC;
+            add_collateral => PUBLIC;
+            borrow_more => PUBLIC;
+            withdraw_collateral => PUBLIC;
+            repay_targeted => PUBLIC;
+            estimate_repay => PUBLIC;
+            repay_loan => PUBLIC;
+            repay_many => PUBLIC;
+            close_position => PUBLIC;
+            merge_positions => PUBLIC;
+            split_position => PUBLIC;
+            set_delegate => PUBLIC;
+            delegate_add_collateral => PUBLIC;
+            delegate_rebalance => PUBLIC;
+            repay_for => PUBLIC;
+            liquidate => PUBLIC;
+            redeem => PUBLIC;
+            deposit_to_pool => PUBLIC;
+            withdraw_from_pool => PUBLIC;
+            liquidate_with_pool => PUBLIC;
+            preview_supply => PUBLIC;
+            preview_withdraw => PUBLIC;
+            preview_borrow_more => PUBLIC;
+            preview_add_collateral => PUBLIC;
+            preview_withdraw_collateral => PUBLIC;
+            preview_repay_targeted => PUBLIC;
+            preview_repay_for => PUBLIC;
+            preview_liquidate => PUBLIC;
+            preview_redeem => PUBLIC;
+            preview_merge_positions => PUBLIC;
+            preview_split_position => PUBLIC;
+            preview_rebalance => PUBLIC;
+            get_protocol_stats => PUBLIC;
+            list_assets => PUBLIC;
+            get_health_many => PUBLIC;
+            get_delegates => PUBLIC;
+            get_position_history => PUBLIC;
+            get_riskiest_positions => PUBLIC;
+            get_pool_deposit => PUBLIC;
+            refresh_position => PUBLIC;
         }
     }
 
@@ -21,12 +104,47 @@ mod radish {
     struct Radish {
         // Asset Storage
         asset_list: AvlTree<Decimal, ResourceAddress>,
+        asset_configs: KeyValueStore<ResourceAddress, AssetConfig>,
+        next_asset_id: Decimal,
         vaults: KeyValueStore<ResourceAddress, Vault>,
+        total_supplied: KeyValueStore<ResourceAddress, Decimal>, // Collateral held on behalf of positions
+        reserves: KeyValueStore<ResourceAddress, Decimal>,       // Collateral retained by the protocol
+        // Radish Resources
+        radish_manager: ResourceManager,
+        // Borrower Resources
+        borrower_manager: ResourceManager,
+        // Stability Pool
+        depositor_manager: ResourceManager,
+        pool_vault: Vault,                                  // RSH deposited, burnt as liquidations are absorbed
+        pool_gains: KeyValueStore<ResourceAddress, Vault>, // Collateral seized for depositors
+        pool_product: Decimal,
+        pool_epoch: u64,
+        pool_scale: u64,
+        pool_sums: KeyValueStore<(u64, u64), AddrToAmount>, // (Epoch, scale) -> sum of each asset, see `math::pool_product`
+        // Placeholder Oracle
+        oracle_prices: KeyValueStore<ResourceAddress, Decimal>, // Resource -> USD
+        // Protocol State
+        parameters: ProtocolParameters,
+        paused: bool,
+        interest_index: Decimal,
+        last_accrual: i64,
+        total_debt: Decimal,
+        open_positions: u64, // Borrower badges minted and not yet closed
+        base_rate: Decimal,   // Redemption base rate as of the last redemption
+        last_redemption: i64,
+        // Positions
+        history: KeyValueStore<NonFungibleLocalId, PositionHistory>, // Kept after a position is closed
+        liquidation_index: AvlTree<(Decimal, NonFungibleLocalId), ()>, // (Health factor, position) of indebted positions
+        index_keys: KeyValueStore<NonFungibleLocalId, Decimal>,        // Health factor each position is indexed under
+        // Delegation
+        delegates: KeyValueStore<NonFungibleLocalId, Delegation>,
     }
 
     impl Radish {
         /* -------------- Public Methods -------------- */
         pub fn instantiate() -> (Global<Radish>, Bucket) {
+            let (address_reservation, component_address) = Runtime::allocate_component_address(Radish::blueprint_id());
+
             // Keep track of the number of assets supported by Radish
             let mut _asset_count: Decimal = dec!(-1);
             let mut asset_count = || {
@@ -50,9 +168,13 @@ mod radish {
             let admin_resource_manager: ResourceManager = ResourceBuilder::new_fungible(OwnerRole::None)
                 .divisibility(DIVISIBILITY_NONE)
                 .metadata(metadata! {init {
-                    "name" => "", locked;
-                    "description" => "", locked;
+                    "name"        => "Radish Admin Badge", locked;
+                    "description" => "Badge allowing the holder to manage assets and parameters of the Radish lending platform", locked;
                 }})
+                .mint_roles(mint_roles! {
+                    minter => owner_access_rule.clone();
+                    minter_updater => rule!(deny_all);
+                })
                 .create_with_no_initial_supply();
             let admin_access_rule: AccessRule = rule!(require(admin_resource_manager.address()));
 
@@ -64,7 +186,9 @@ mod radish {
 
             /* ------------ Internal Data Setup ----------- */
             let mut asset_list: AvlTree<Decimal, ResourceAddress> = AvlTree::new();
+            let asset_configs: KeyValueStore<ResourceAddress, AssetConfig> = KeyValueStore::new();
             let asset_vaults: KeyValueStore<ResourceAddress, Vault> = KeyValueStore::new();
+            let oracle_prices: KeyValueStore<ResourceAddress, Decimal> = KeyValueStore::new();
 
             // Radish
             let radish_bucket: Bucket = ResourceBuilder::new_fungible(OwnerRole::None)
@@ -74,19 +198,92 @@ mod radish {
                     "symbol"      => "RSH",    locked;
                     "description" => "",       locked;
                 }})
+                .mint_roles(mint_roles! {
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .burn_roles(burn_roles! {
+                    burner => rule!(require(global_caller(component_address)));
+                    burner_updater => rule!(deny_all);
+                })
                 .mint_initial_supply(10000)
                 .into();
+            let radish_manager: ResourceManager = radish_bucket.resource_manager();
             let radish_vault: Vault = Vault::with_bucket(radish_bucket);
 
-            asset_list.insert(asset_count(), radish_vault.resource_address());
+            // RSH is listed so it shows up in the asset list, but is never accepted as collateral
+            let radish_id: Decimal = asset_count();
+            asset_list.insert(radish_id, radish_vault.resource_address());
+            asset_configs.insert(
+                radish_vault.resource_address(),
+                AssetConfig {
+                    id: radish_id,
+                    status: AssetStatus::Frozen,
+                    risk: RiskConfig {
+                        loan_to_value: dec!(0),
+                        liquidation_threshold: dec!(0),
+                        liquidation_bonus: dec!(0),
+                        supply_cap: dec!(0),
+                    },
+                },
+            );
+            oracle_prices.insert(radish_vault.resource_address(), dec!(1));
             asset_vaults.insert(radish_vault.resource_address(), radish_vault);
 
             // XRD
             let xrd_vault: Vault = Vault::new(XRD);
 
-            asset_list.insert(asset_count(), xrd_vault.resource_address());
+            let xrd_id: Decimal = asset_count();
+            asset_list.insert(xrd_id, xrd_vault.resource_address());
+            asset_configs.insert(
+                xrd_vault.resource_address(),
+                AssetConfig {
+                    id: xrd_id,
+                    status: AssetStatus::Active,
+                    risk: RiskConfig {
+                        loan_to_value: dec!(0.5),
+                        liquidation_threshold: dec!(0.65),
+                        liquidation_bonus: dec!(0.1),
+                        supply_cap: Decimal::MAX,
+                    },
+                },
+            );
             asset_vaults.insert(xrd_vault.resource_address(), xrd_vault);
 
+            // Borrower
+            let borrower_manager: ResourceManager = ResourceBuilder::new_ruid_non_fungible::<Borrower>(OwnerRole::None)
+                .metadata(metadata! {init {
+                    "name" => "Radish Borrower Badge", locked;
+                }})
+                .mint_roles(mint_roles! {
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .burn_roles(burn_roles! {
+                    burner => rule!(require(global_caller(component_address)));
+                    burner_updater => rule!(deny_all);
+                })
+                .non_fungible_data_update_roles(non_fungible_data_update_roles! {
+                    non_fungible_data_updater => rule!(require(global_caller(component_address)));
+                    non_fungible_data_updater_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
+
+            // Stability Pool Depositor
+            let depositor_manager: ResourceManager = ResourceBuilder::new_ruid_non_fungible::<Depositor>(OwnerRole::None)
+                .metadata(metadata! {init {
+                    "name" => "Radish Stability Pool Depositor Badge", locked;
+                }})
+                .mint_roles(mint_roles! {
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .non_fungible_data_update_roles(non_fungible_data_update_roles! {
+                    non_fungible_data_updater => rule!(require(global_caller(component_address)));
+                    non_fungible_data_updater_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
+
             /* ----------------- Component ---------------- */
             //* Metadata
             let component_metadata = metadata! {
@@ -105,7 +302,40 @@ mod radish {
             // Instantising the component
             let component_data: Radish = Self {
                 asset_list,
+                asset_configs,
+                next_asset_id: asset_count(),
                 vaults: asset_vaults,
+                total_supplied: KeyValueStore::new(),
+                reserves: KeyValueStore::new(),
+                radish_manager,
+                borrower_manager,
+                depositor_manager,
+                pool_vault: Vault::new(radish_manager.address()),
+                pool_gains: KeyValueStore::new(),
+                pool_product: dec!(1),
+                pool_epoch: 0,
+                pool_scale: 0,
+                pool_sums: KeyValueStore::new(),
+                oracle_prices,
+                parameters: ProtocolParameters {
+                    borrow_rate: dec!(0.05),
+                    debt_ceiling: Decimal::MAX,
+                    close_factor: dec!(0.5),
+                    dust_threshold: dec!(0.01),
+                    withdrawal_health_factor: dec!(1.25),
+                    redemption_fee_floor: dec!(0.005),
+                },
+                paused: false,
+                interest_index: dec!(1),
+                last_accrual: Clock::current_time_rounded_to_seconds().seconds_since_unix_epoch,
+                total_debt: dec!(0),
+                open_positions: 0,
+                base_rate: dec!(0),
+                last_redemption: 0,
+                history: KeyValueStore::new(),
+                liquidation_index: AvlTree::new(),
+                index_keys: KeyValueStore::new(),
+                delegates: KeyValueStore::new(),
             };
 
             let component: Global<Radish> = component_data
@@ -113,23 +343,36 @@ mod radish {
                 .prepare_to_globalize(OwnerRole::Fixed(owner_access_rule.clone()))
                 .roles(component_roles)
                 .metadata(component_metadata)
+                .with_address(address_reservation)
                 .globalize();
 
             (component, owner_badge)
         }
 
+        /* ---------------- Admin Methods --------------- */
         /// Adds a (fungible) asset into the asset list and create a corresponding vault
-        pub fn add_asset(&mut self, asset: ResourceAddress) {
+        pub fn add_asset(&mut self, asset: ResourceAddress, risk: RiskConfig) {
             // Pre-run Checks
-            assert!(!asset.is_fungible(), "Provided asset must be fungible.");
+            ensure!(asset.is_fungible(), RadishError::AssetNotFungible { asset });
+            ensure!(self.asset_configs.get(&asset).is_none(), RadishError::AssetAlreadyListed { asset });
+            Self::validate_risk_config(&risk);
+
+            // Update the asset list and create a vault
+            let config: AssetConfig = AssetConfig {
+                id: self.next_asset_id,
+                status: AssetStatus::Active,
+                risk,
+            };
+            self.next_asset_id += dec!(1);
 
-            for (_, list_asset, _) in self.asset_list.range(dec!(0)..self.asset_list_length()) {
-                assert!(asset == list_asset, "Cannot add asset {:?}, as it is already added.", asset)
+            self.asset_list.insert(config.id, asset);
+            self.asset_configs.insert(asset, config.clone());
+            // Vaults of previously removed assets are kept, re-use them if present
+            if self.vaults.get(&asset).is_none() {
+                self.vaults.insert(asset, Vault::new(asset));
             }
 
-            // Update the asset list and create a vault
-            self.asset_list.insert(self.asset_list_length(), asset);
-            self.vaults.insert(asset, Vault::new(asset));
+            Runtime::emit_event(AssetAddedEvent { asset, config });
         }
 
         /// Removes a (fungible) asset from the asset list, but does not remove its vault
@@ -138,38 +381,2144 @@ mod radish {
         /// ! This function cannot destroy a vault, only removes it from the assets list and thus prevents it from being used
         pub fn remove_asset(&mut self, asset: ResourceAddress) {
             // Pre-run Checks
-            assert!(!asset.is_fungible(), "Provided asset must be fungible.");
-
-            let mut found: bool = false;
-            let mut index: Decimal = Decimal::MIN;
-            for (i, list_asset, _) in self.asset_list.range(dec!(0)..self.asset_list_length()) {
-                if asset == list_asset {
-                    found = true;
-                    index = i;
-                    break;
+            ensure!(asset.is_fungible(), RadishError::AssetNotFungible { asset });
+            ensure!(asset != self.radish_manager.address(), RadishError::RadishNotConfigurable);
+
+            let index: Decimal = match self.asset_configs.get(&asset) {
+                Some(config) => config.id,
+                None => RadishError::AssetNotListed { asset }.raise(),
+            };
+
+            let amount: Decimal = self.vaults.get(&asset).unwrap().amount();
+            ensure!(amount == Decimal::ZERO, RadishError::AssetVaultNotEmpty { asset, amount });
+
+            // Remove the asset from the list
+            self.asset_list.remove(&index);
+            self.asset_configs.remove(&asset);
+
+            Runtime::emit_event(AssetRemovedEvent { asset, id: index });
+        }
+
+        /// Updates the status and risk configuration of a listed asset
+        pub fn update_asset(&mut self, asset: ResourceAddress, status: AssetStatus, risk: RiskConfig) {
+            ensure!(asset != self.radish_manager.address(), RadishError::RadishNotConfigurable);
+            Self::validate_risk_config(&risk);
+
+            let config: AssetConfig = {
+                let mut config = self
+                    .asset_configs
+                    .get_mut(&asset)
+                    .unwrap_or_else(|| RadishError::AssetNotListed { asset }.raise());
+                config.status = status;
+                config.risk = risk;
+                config.clone()
+            };
+
+            Runtime::emit_event(AssetUpdatedEvent { asset, config });
+        }
+
+        /// Sets the placeholder oracle price (in USD) of a listed asset
+        pub fn set_price(&mut self, asset: ResourceAddress, price: Decimal) {
+            ensure!(self.asset_configs.get(&asset).is_some(), RadishError::AssetNotListed { asset });
+            ensure!(price > Decimal::ZERO, RadishError::InvalidPrice { asset, price });
+
+            self.accrue_interest();
+            self.oracle_prices.insert(asset, price);
+
+            Runtime::emit_event(OracleUpdateEvent { asset, price });
+        }
+
+        pub fn set_paused(&mut self, paused: bool) {
+            self.paused = paused;
+
+            Runtime::emit_event(PauseToggleEvent { paused });
+        }
+
+        pub fn set_parameters(&mut self, parameters: ProtocolParameters) {
+            let invalid = |field: &str, value: Decimal| RadishError::InvalidParameter {
+                field: field.to_string(),
+                value,
+            };
+            ensure!(parameters.borrow_rate >= Decimal::ZERO, invalid("borrow_rate", parameters.borrow_rate));
+            ensure!(parameters.debt_ceiling >= Decimal::ZERO, invalid("debt_ceiling", parameters.debt_ceiling));
+            ensure!(
+                parameters.close_factor > Decimal::ZERO && parameters.close_factor <= Decimal::ONE,
+                invalid("close_factor", parameters.close_factor)
+            );
+            ensure!(parameters.dust_threshold >= Decimal::ZERO, invalid("dust_threshold", parameters.dust_threshold));
+            ensure!(
+                parameters.withdrawal_health_factor >= Decimal::ONE,
+                invalid("withdrawal_health_factor", parameters.withdrawal_health_factor)
+            );
+            ensure!(
+                parameters.redemption_fee_floor >= Decimal::ZERO && parameters.redemption_fee_floor <= Decimal::ONE,
+                invalid("redemption_fee_floor", parameters.redemption_fee_floor)
+            );
+
+            // Accrue with the old rate before switching to the new one
+            self.accrue_interest();
+            self.parameters = parameters.clone();
+
+            Runtime::emit_event(ParameterChangeEvent { parameters });
+        }
+
+        /* --------------- Lending Methods -------------- */
+        /// Checkpoints the interest index, accruing interest on all outstanding debt
+        pub fn accrue_interest(&mut self) {
+            let now: i64 = Clock::current_time_rounded_to_seconds().seconds_since_unix_epoch;
+            if now <= self.last_accrual {
+                return;
+            }
+
+            let new_index: Decimal = self.current_index();
+            let new_total_debt: Decimal = math::scale_debt(self.total_debt, new_index, self.interest_index);
+            let interest: Decimal = new_total_debt.checked_sub(self.total_debt).unwrap();
+
+            self.interest_index = new_index;
+            self.total_debt = new_total_debt;
+            self.last_accrual = now;
+
+            Runtime::emit_event(InterestAccrualEvent {
+                interest_index: new_index,
+                interest,
+                total_debt: new_total_debt,
+                timestamp: now,
+            });
+        }
+
+        /// Estimates the amount of RSH which can be borrowed against the given collateral
+        pub fn estimate_loan(&self, collateral: AddrToAmount) -> Decimal {
+            /* ---------------- Validation ---------------- */
+            ensure!(!collateral.is_empty(), RadishError::NoCollateral);
+            self.validate_collateral(&collateral);
+
+            /* ------------------ Return ------------------ */
+            self.borrow_capacity(&collateral)
+        }
+
+        // Vec alright here since max 3-4 values passed
+        pub fn get_loan(&mut self, collateral: Vec<Bucket>) -> (Bucket, Bucket) {
+            self.accrue_interest();
+
+            let resource_map: AddrToAmount = Self::bucket_amounts(&collateral);
+            let estimated_rsh: Decimal = self.plan_supply(&resource_map).borrowed;
+
+            let borrower_badge: Bucket = self
+                .borrower_manager
+                .mint_ruid_non_fungible(self.new_borrower(resource_map.clone(), estimated_rsh));
+            let borrower_id: NonFungibleLocalId = borrower_badge.as_non_fungible().non_fungible_local_id();
+
+            self.deposit_collateral(&borrower_id, collateral, &resource_map, estimated_rsh);
+
+            self.record(
+                &borrower_id,
+                PositionAction::Open { borrowed: estimated_rsh },
+                resource_map.clone(),
+                estimated_rsh,
+            );
+            self.update_index(&borrower_id, &resource_map, estimated_rsh);
+
+            self.total_debt = self.total_debt.checked_add(estimated_rsh).unwrap();
+            self.open_positions += 1;
+            Runtime::emit_event(BorrowEvent {
+                position: borrower_id,
+                resource: self.radish_manager.address(),
+                amount: estimated_rsh,
+                collateral: resource_map,
+                debt: estimated_rsh,
+                total_debt: self.total_debt,
+            });
+
+            (borrower_badge, self.radish_manager.mint(estimated_rsh))
+        }
+
+        /// Repays RSH on behalf of any position, e.g. by a guardian or protection bot, returning the excess
+        ///
+        /// ! No collateral is released to the payer, it stays with the position
+        pub fn repay_for(&mut self, borrower_id: NonFungibleLocalId, mut repayment: Bucket) -> Bucket {
+            self.validate_repayment(&repayment);
+            self.accrue_interest();
+
+            let plan: PositionPreview = self.plan_repay_for(&self.borrower(&borrower_id), repayment.amount());
+            self.update_borrower(
+                &borrower_id,
+                plan.collateral.clone(),
+                plan.debt,
+                PositionAction::Repay {
+                    repaid: plan.repaid,
+                    released: AddrToAmount::new(),
+                },
+            );
+
+            repayment.take(plan.repaid).burn();
+            self.total_debt = self.total_debt.checked_sub(plan.repaid).unwrap().max(Decimal::ZERO);
+
+            Runtime::emit_event(RepayEvent {
+                position: borrower_id,
+                resource: self.radish_manager.address(),
+                amount: plan.repaid,
+                collateral: plan.collateral,
+                debt: plan.debt,
+                total_debt: self.total_debt,
+            });
+
+            repayment
+        }
+
+        /// Burns the badge of a position without collateral or debt
+        pub fn close_position(&mut self, borrower_nft: Bucket) {
+            ensure!(
+                borrower_nft.amount() == Decimal::ONE && borrower_nft.resource_address() == self.borrower_manager.address(),
+                RadishError::InvalidBorrowerBadge {
+                    resource: borrower_nft.resource_address(),
+                    amount: borrower_nft.amount(),
+                }
+            );
+            self.accrue_interest();
+
+            let borrower_id: NonFungibleLocalId = borrower_nft.as_non_fungible().non_fungible_local_id();
+            let borrower_data: Borrower = self.borrower(&borrower_id);
+            let debt: Decimal = self.current_debt(&borrower_data);
+            ensure!(
+                debt == Decimal::ZERO && borrower_data.collateral.values().all(|amount| *amount == Decimal::ZERO),
+                RadishError::PositionNotEmpty {
+                    collateral: borrower_data.collateral,
+                    debt,
+                }
+            );
+
+            borrower_nft.burn();
+            self.open_positions -= 1;
+            self.delegates.remove(&borrower_id);
+            self.update_index(&borrower_id, &AddrToAmount::new(), Decimal::ZERO);
+            self.record(&borrower_id, PositionAction::Close, AddrToAmount::new(), Decimal::ZERO);
+        }
+
+        /// Deposits more collateral into an existing position, e.g. to rescue it from liquidation
+        pub fn add_collateral(&mut self, position_proof: NonFungibleProof, collateral: Vec<Bucket>) {
+            let borrower_id: NonFungibleLocalId = self.position_id(position_proof);
+            self.accrue_interest();
+
+            let added: AddrToAmount = Self::bucket_amounts(&collateral);
+            let plan: PositionPreview = self.plan_add_collateral(&self.borrower(&borrower_id), &added);
+
+            self.deposit_collateral(&borrower_id, collateral, &plan.collateral, plan.debt);
+            self.update_borrower(&borrower_id, plan.collateral, plan.debt, PositionAction::AddCollateral { added });
+        }
+
+        /// Borrows more RSH against an existing position, up to the remaining capacity of its collateral
+        pub fn borrow_more(&mut self, position_proof: NonFungibleProof, amount: Decimal) -> Bucket {
+            let borrower_id: NonFungibleLocalId = self.position_id(position_proof);
+            self.accrue_interest();
+
+            let plan: PositionPreview = self.plan_borrow_more(&self.borrower(&borrower_id), amount);
+            self.update_borrower(&borrower_id, plan.collateral.clone(), plan.debt, PositionAction::Borrow { amount });
+            self.total_debt = self.total_debt.checked_add(plan.borrowed).unwrap();
+
+            Runtime::emit_event(BorrowEvent {
+                position: borrower_id,
+                resource: self.radish_manager.address(),
+                amount: plan.borrowed,
+                collateral: plan.collateral,
+                debt: plan.debt,
+                total_debt: self.total_debt,
+            });
+
+            self.radish_manager.mint(plan.borrowed)
+        }
+
+        /// Withdraws one collateral asset from a position without repaying, as long as it stays healthy by a safety margin
+        pub fn withdraw_collateral(&mut self, position_proof: NonFungibleProof, asset: ResourceAddress, amount: Decimal) -> Bucket {
+            let borrower_id: NonFungibleLocalId = self.position_id(position_proof);
+            self.accrue_interest();
+
+            let plan: PositionPreview = self.plan_withdraw_collateral(&self.borrower(&borrower_id), asset, amount);
+            let mut released: Vec<Bucket> = self.release_collateral(&borrower_id, &plan.released, &plan.collateral, plan.debt);
+            self.update_borrower(
+                &borrower_id,
+                plan.collateral,
+                plan.debt,
+                PositionAction::WithdrawCollateral { released: plan.released },
+            );
+
+            released.pop().unwrap()
+        }
+
+        /// Repays RSH, releasing the value of the repaid share of the collateral from a single chosen asset
+        ///
+        /// ! Unlike `repay_loan`, the position is never closed; once its debt is repaid the rest of its collateral can be
+        /// ! taken out with `withdraw_collateral`
+        pub fn repay_targeted(&mut self, position_proof: NonFungibleProof, mut repayment: Bucket, asset: ResourceAddress) -> Vec<Bucket> {
+            let borrower_id: NonFungibleLocalId = self.position_id(position_proof);
+            self.validate_repayment(&repayment);
+            self.accrue_interest();
+
+            let plan: PositionPreview = self.plan_repay_targeted(&self.borrower(&borrower_id), repayment.amount(), asset);
+            let mut released: Vec<Bucket> = self.release_collateral(&borrower_id, &plan.released, &plan.collateral, plan.debt);
+            self.update_borrower(
+                &borrower_id,
+                plan.collateral.clone(),
+                plan.debt,
+                PositionAction::Repay {
+                    repaid: plan.repaid,
+                    released: plan.released,
+                },
+            );
+
+            repayment.take(plan.repaid).burn();
+            self.total_debt = self.total_debt.checked_sub(plan.repaid).unwrap().max(Decimal::ZERO);
+            released.push(repayment);
+
+            Runtime::emit_event(RepayEvent {
+                position: borrower_id,
+                resource: self.radish_manager.address(),
+                amount: plan.repaid,
+                collateral: plan.collateral,
+                debt: plan.debt,
+                total_debt: self.total_debt,
+            });
+
+            released
+        }
+
+        /// Estimates the collateral released by repaying the given amount of RSH
+        pub fn estimate_repay(&self, borrower_id: NonFungibleLocalId, repayment: Decimal) -> AddrToAmount {
+            self.plan_repay(&self.borrower(&borrower_id), repayment).released
+        }
+
+        /// Repays RSH, releasing collateral pro rata to the repaid share of the debt
+        ///
+        /// ! A fully repaid position is left empty rather than burnt, as only a proof of its badge is passed in;
+        /// ! it can be reused, or burnt with `close_position`
+        pub fn repay_loan(&mut self, position_proof: NonFungibleProof, repayment: Bucket) -> Vec<Bucket> {
+            let borrower_id: NonFungibleLocalId = self.position_id(position_proof);
+            self.validate_repayment(&repayment);
+            self.accrue_interest();
+
+            self.repay(borrower_id, repayment)
+        }
+
+        /// Repays several positions of the proof from one RSH bucket, worst health factor first
+        ///
+        /// Each position gets at most its requested amount, until the RSH runs out. Released collateral and the unspent
+        /// RSH are returned merged into one bucket per resource
+        pub fn repay_many(
+            &mut self,
+            positions_proof: NonFungibleProof,
+            repayments: Vec<(NonFungibleLocalId, Decimal)>,
+            mut repayment: Bucket,
+        ) -> Vec<Bucket> {
+            let proven: IndexSet<NonFungibleLocalId> = self.position_ids(positions_proof);
+            self.validate_repayment(&repayment);
+            self.accrue_interest();
+
+            // Keep the request order for positions of equal health, merging duplicate requests
+            let mut requested: IndexMap<NonFungibleLocalId, Decimal> = IndexMap::new();
+            for (borrower_id, amount) in repayments {
+                let total = requested.entry(borrower_id).or_insert(Decimal::ZERO);
+                *total = total.checked_add(amount).unwrap();
+            }
+            for borrower_id in requested.keys() {
+                ensure!(
+                    proven.contains(borrower_id),
+                    RadishError::PositionNotProven {
+                        position: borrower_id.clone(),
+                    }
+                );
+            }
+
+            let mut released: HashMap<ResourceAddress, Bucket> = HashMap::new();
+            for health in self.health_many(requested.keys().cloned().collect()) {
+                let amount: Decimal = requested[&health.position].min(repayment.amount());
+                if amount <= Decimal::ZERO {
+                    continue;
+                }
+
+                let buckets: Vec<Bucket> = self.repay(health.position, repayment.take(amount));
+                Self::merge_buckets(&mut released, buckets);
+            }
+
+            let mut released: Vec<Bucket> = released.into_values().collect();
+            if repayment.is_empty() {
+                repayment.drop_empty();
+            } else {
+                released.push(repayment);
+            }
+            released
+        }
+
+        /// Merges all positions of the proof into the first one, preserving their total collateral and debt
+        ///
+        /// ! The other positions are left empty, and can be burnt with `close_position`
+        pub fn merge_positions(&mut self, positions_proof: NonFungibleProof) {
+            let borrower_ids: Vec<NonFungibleLocalId> = self.position_ids(positions_proof).into_iter().collect();
+            self.accrue_interest();
+
+            let borrowers: Vec<Borrower> = borrower_ids.iter().map(|borrower_id| self.borrower(borrower_id)).collect();
+            let plan: PositionPreview = self.plan_merge(&borrowers);
+
+            let mut remainders: AddrToAmount = AddrToAmount::new();
+            for (&asset, &amount) in borrowers.iter().flat_map(|borrower| borrower.remainders.iter()) {
+                let total = remainders.entry(asset).or_insert(Decimal::ZERO);
+                *total = total.checked_add(amount).unwrap();
+            }
+            let position: NonFungibleLocalId = borrower_ids[0].clone();
+            for borrower_id in borrower_ids[1..].iter() {
+                self.update_borrower(
+                    borrower_id,
+                    AddrToAmount::new(),
+                    Decimal::ZERO,
+                    PositionAction::MergedInto {
+                        position: position.clone(),
+                    },
+                );
+            }
+
+            self.update_borrower(
+                &position,
+                plan.collateral.clone(),
+                plan.debt,
+                PositionAction::Merge {
+                    merged: borrower_ids[1..].to_vec(),
+                },
+            );
+            self.borrower_manager
+                .update_non_fungible_data(&position, "remainders", remainders);
+
+            Runtime::emit_event(MergeEvent {
+                position,
+                merged: borrower_ids[1..].to_vec(),
+                collateral: plan.collateral,
+                debt: plan.debt,
+            });
+        }
+
+        /// Moves a fraction of a position's collateral and debt into a new position, returning its badge
+        pub fn split_position(&mut self, position_proof: NonFungibleProof, fraction: Decimal) -> Bucket {
+            let borrower_id: NonFungibleLocalId = self.position_id(position_proof);
+            self.accrue_interest();
+
+            let borrower: Borrower = self.borrower(&borrower_id);
+            let (kept, split) = self.plan_split(&borrower, fraction);
+            let borrower_badge: Bucket = self
+                .borrower_manager
+                .mint_ruid_non_fungible(self.new_borrower(split.collateral.clone(), split.debt));
+            let new_position: NonFungibleLocalId = borrower_badge.as_non_fungible().non_fungible_local_id();
+            self.open_positions += 1;
+
+            self.update_borrower(
+                &borrower_id,
+                kept.collateral.clone(),
+                kept.debt,
+                PositionAction::Split {
+                    new_position: new_position.clone(),
+                    fraction,
+                },
+            );
+            self.record(
+                &new_position,
+                PositionAction::SplitFrom {
+                    position: borrower_id.clone(),
+                },
+                split.collateral.clone(),
+                split.debt,
+            );
+            self.update_index(&new_position, &split.collateral, split.debt);
+
+            // Carried remainders are split with the collateral they were rounded from
+            let mut kept_remainders: AddrToAmount = AddrToAmount::new();
+            let mut split_remainders: AddrToAmount = AddrToAmount::new();
+            for (&asset, &carried) in borrower.remainders.iter() {
+                let moved: Decimal = math::mul_div(carried, fraction, Decimal::ONE, math::ROUND_DOWN)
+                    .min(split.collateral.get(&asset).copied().unwrap_or(Decimal::ZERO));
+                kept_remainders.insert(asset, carried.checked_sub(moved).unwrap());
+                split_remainders.insert(asset, moved);
+            }
+            self.borrower_manager
+                .update_non_fungible_data(&borrower_id, "remainders", kept_remainders);
+            self.borrower_manager
+                .update_non_fungible_data(&new_position, "remainders", split_remainders);
+
+            Runtime::emit_event(SplitEvent {
+                position: borrower_id,
+                new_position,
+                fraction,
+                collateral: kept.collateral,
+                debt: kept.debt,
+                new_collateral: split.collateral,
+                new_debt: split.debt,
+            });
+
+            borrower_badge
+        }
+
+        /* ---------------- Delegation ---------------- */
+        /// Grants a delegate, e.g. an account's signature badge, a component badge or a bot badge, the given permissions
+        /// on the proven position, replacing any earlier ones; empty permissions revoke the delegation
+        ///
+        /// `holder` is the account holding the position's badge. Delegations lapse once it no longer holds it, so they do
+        /// not pass to the next holder of a transferred badge, and are dropped once the position is closed
+        pub fn set_delegate(
+            &mut self,
+            position_proof: NonFungibleProof,
+            holder: Global<Account>,
+            delegate: ResourceOrNonFungible,
+            permissions: DelegatePermissions,
+        ) {
+            let borrower_id: NonFungibleLocalId = self.position_id(position_proof);
+            let holder: ComponentAddress = holder.address();
+            ensure!(
+                self.held_by(holder, &borrower_id),
+                RadishError::PositionNotHeld {
+                    position: borrower_id.clone(),
+                    holder,
                 }
+            );
+
+            // Grants of an earlier holder are not carried over
+            let mut delegates: IndexMap<ResourceOrNonFungible, DelegatePermissions> = self
+                .delegates
+                .get(&borrower_id)
+                .filter(|delegation| delegation.holder == holder)
+                .map(|delegation| delegation.delegates.clone())
+                .unwrap_or_default();
+            if permissions.is_empty() {
+                delegates.shift_remove(&delegate);
+            } else {
+                delegates.insert(delegate.clone(), permissions);
             }
+            self.delegates.insert(borrower_id.clone(), Delegation { holder, delegates });
+
+            Runtime::emit_event(DelegateEvent {
+                position: borrower_id,
+                holder,
+                delegate,
+                permissions,
+            });
+        }
 
-            assert!(
-                !found || index == Decimal::MIN,
-                "Cannot find asset [{:?}] in the asset list. It is likely not added.",
-                asset
+        /// Deposits more collateral into a position as one of its delegates
+        pub fn delegate_add_collateral(&mut self, delegate: ResourceOrNonFungible, borrower_id: NonFungibleLocalId, collateral: Vec<Bucket>) {
+            self.authorize_delegate(&borrower_id, &delegate, |permissions| permissions.add_collateral);
+            self.accrue_interest();
+
+            let added: AddrToAmount = Self::bucket_amounts(&collateral);
+            let plan: PositionPreview = self.plan_add_collateral(&self.borrower(&borrower_id), &added);
+
+            self.deposit_collateral(&borrower_id, collateral, &plan.collateral, plan.debt);
+            self.update_borrower(&borrower_id, plan.collateral, plan.debt, PositionAction::AddCollateral { added });
+        }
+
+        /// Moves collateral from one position into another, both delegating rebalancing to the same badge and held by the
+        /// same account, so a delegate can never move collateral out of its holder's hands
+        ///
+        /// The source position must keep the withdrawal health factor, as if the collateral had been withdrawn
+        pub fn delegate_rebalance(
+            &mut self,
+            delegate: ResourceOrNonFungible,
+            from: NonFungibleLocalId,
+            to: NonFungibleLocalId,
+            asset: ResourceAddress,
+            amount: Decimal,
+        ) {
+            let holder: ComponentAddress = self.authorize_delegate(&from, &delegate, |permissions| permissions.rebalance);
+            let to_holder: ComponentAddress = self.authorize_delegate(&to, &delegate, |permissions| permissions.rebalance);
+            ensure!(
+                to_holder == holder,
+                RadishError::PositionNotHeld {
+                    position: to.clone(),
+                    holder,
+                }
             );
-            assert!(
-                !self.vaults.get_mut(&asset).unwrap().is_empty(),
-                "Internal vault for the asset [{:?}] is not empty; cannot delete the asset.",
-                asset
+            self.accrue_interest();
+
+            // Collateral stays in the vaults, only the positions' shares of it change
+            let (source, destination) = self.plan_rebalance(&from, &to, asset, amount);
+            let action: PositionAction = PositionAction::Rebalance {
+                from: from.clone(),
+                to: to.clone(),
+                asset,
+                amount,
+            };
+            self.update_borrower(&from, source.collateral.clone(), source.debt, action.clone());
+            self.update_borrower(&to, destination.collateral.clone(), destination.debt, action);
+
+            Runtime::emit_event(RebalanceEvent {
+                delegate,
+                from,
+                to,
+                asset,
+                amount,
+                from_collateral: source.collateral,
+                to_collateral: destination.collateral,
+            });
+        }
+
+        /// Repays part of an unhealthy position's debt in exchange for its collateral plus a bonus
+        pub fn liquidate(&mut self, borrower_id: NonFungibleLocalId, mut repayment: Bucket) -> Vec<Bucket> {
+            self.validate_repayment(&repayment);
+            self.accrue_interest();
+
+            let plan: PositionPreview = self.plan_liquidate(&self.borrower(&borrower_id), repayment.amount());
+
+            let mut released: Vec<Bucket> = self.release_collateral(&borrower_id, &plan.released, &plan.collateral, plan.debt);
+            self.update_borrower(
+                &borrower_id,
+                plan.collateral.clone(),
+                plan.debt,
+                PositionAction::Liquidation {
+                    repaid: plan.repaid,
+                    seized: plan.released.clone(),
+                },
             );
 
-            // Remove the asset from the list
-            self.asset_list.remove(&index);
+            repayment.take(plan.repaid).burn();
+            self.total_debt = self.total_debt.checked_sub(plan.repaid).unwrap().max(Decimal::ZERO);
+            released.push(repayment);
 
-            // TODO: find some way to release the funds from the vault; might cause some problems with customers im just sayin
+            Runtime::emit_event(LiquidateEvent {
+                position: borrower_id,
+                resource: self.radish_manager.address(),
+                repaid: plan.repaid,
+                seized: plan.released,
+                collateral: plan.collateral,
+                debt: plan.debt,
+                total_debt: self.total_debt,
+            });
+
+            released
         }
 
-        /* -------------- Private Methods ------------- */
-        fn asset_list_length(&self) -> Decimal {
-            return Decimal::from(self.asset_list.get_length());
+        /// Burns RSH for collateral worth as much at oracle prices, less a fee, drawn from the positions with the
+        /// lowest health factors, whose debt is reduced by the same amount. Returns the collateral and any RSH left over
+        ///
+        /// The fee rises with each redemption by half the redeemed share of the total debt, decaying back to the fee floor
+        /// over time. Positions open to liquidation are skipped
+        ///
+        /// ! Positions are picked like `get_riskiest_positions`, so one pushed out of the re-ranked window of the index by a
+        /// ! later price move is passed over until refreshed
+        pub fn redeem(&mut self, mut redemption: Bucket) -> Vec<Bucket> {
+            self.validate_repayment(&redemption);
+            self.accrue_interest();
+
+            let plan: RedemptionPlan = self.plan_redeem(redemption.amount());
+            let mut released: HashMap<ResourceAddress, Bucket> = HashMap::new();
+            for (borrower_id, position) in plan.positions.iter() {
+                let buckets: Vec<Bucket> = self.release_collateral(borrower_id, &position.released, &position.collateral, position.debt);
+                Self::merge_buckets(&mut released, buckets);
+                for (&address, &amount) in position.fees.iter() {
+                    self.update_total_supplied(address, -amount);
+                    self.update_reserves(address, amount);
+                }
+
+                self.update_borrower(
+                    borrower_id,
+                    position.collateral.clone(),
+                    position.debt,
+                    PositionAction::Redemption {
+                        repaid: position.repaid,
+                        released: position.released.clone(),
+                        fees: position.fees.clone(),
+                    },
+                );
+            }
+
+            redemption.take(plan.redeemed).burn();
+            self.total_debt = self.total_debt.checked_sub(plan.redeemed).unwrap().max(Decimal::ZERO);
+            self.base_rate = plan.base_rate;
+            self.last_redemption = Clock::current_time_rounded_to_seconds().seconds_since_unix_epoch;
+
+            Runtime::emit_event(RedemptionEvent {
+                redeemed: plan.redeemed,
+                fee_rate: plan.fee_rate,
+                base_rate: plan.base_rate,
+                released: plan.released,
+                fees: plan.fees,
+                positions: plan.positions.into_iter().map(|(borrower_id, _)| borrower_id).collect(),
+                total_debt: self.total_debt,
+            });
+
+            let mut released: Vec<Bucket> = released.into_values().collect();
+            if redemption.is_empty() {
+                redemption.drop_empty();
+            } else {
+                released.push(redemption);
+            }
+            released
+        }
+
+        /* --------------- Stability Pool -------------- */
+        /// Deposits RSH into the stability pool, returning a badge to withdraw it and its collateral gains with
+        pub fn deposit_to_pool(&mut self, deposit: Bucket) -> Bucket {
+            ensure!(!self.paused, RadishError::Paused);
+            self.validate_repayment(&deposit);
+
+            let amount: Decimal = deposit.amount();
+            self.pool_vault.put(deposit);
+            let depositor: Bucket = self.depositor_manager.mint_ruid_non_fungible(self.new_depositor(amount));
+
+            Runtime::emit_event(PoolDepositEvent {
+                depositor: depositor.as_non_fungible().non_fungible_local_id(),
+                amount,
+                total_deposits: self.pool_vault.amount(),
+            });
+
+            depositor
+        }
+
+        /// Withdraws up to `amount` of a deposit's remaining RSH along with all of its collateral gains, snapshotting the
+        /// pool for what is left. Withdraw zero to only collect the gains. Only non-empty buckets are returned, so a fully
+        /// absorbed deposit returns no RSH
+        ///
+        /// ! Allowed while paused, depositors can always leave the pool
+        pub fn withdraw_from_pool(&mut self, depositor_proof: NonFungibleProof, amount: Decimal) -> Vec<Bucket> {
+            let depositor_id: NonFungibleLocalId = self.depositor_id(depositor_proof);
+            ensure!(
+                amount >= Decimal::ZERO,
+                RadishError::InvalidAmount {
+                    resource: self.radish_manager.address(),
+                    amount,
+                }
+            );
+
+            let pool_deposit: PoolDeposit = self.pool_deposit(&self.depositor(&depositor_id));
+            let mut withdrawn: Vec<Bucket> = Vec::new();
+            for (&asset, &gain) in pool_deposit.gains.iter() {
+                let mut vault = self.pool_gains.get_mut(&asset).unwrap();
+                let gain: Decimal = gain.min(vault.amount());
+                if gain > Decimal::ZERO {
+                    withdrawn.push(vault.take(gain));
+                }
+            }
+
+            let amount: Decimal = amount.min(pool_deposit.deposit);
+            if amount > Decimal::ZERO {
+                withdrawn.push(self.pool_vault.take(amount));
+            }
+            let deposit: Decimal = pool_deposit.deposit.checked_sub(amount).unwrap();
+            self.update_depositor(&depositor_id, self.new_depositor(deposit));
+
+            Runtime::emit_event(PoolWithdrawEvent {
+                depositor: depositor_id,
+                amount,
+                gains: pool_deposit.gains,
+                deposit,
+                total_deposits: self.pool_vault.amount(),
+            });
+
+            withdrawn
+        }
+
+        /// Liquidates an unhealthy position with RSH from the stability pool, which is burnt, sharing the seized collateral
+        /// among depositors pro rata to their deposits
+        ///
+        /// Repays as much as `liquidate` would, limited by the RSH in the pool
+        pub fn liquidate_with_pool(&mut self, borrower_id: NonFungibleLocalId) {
+            self.accrue_interest();
+            let deposits: Decimal = self.pool_vault.amount();
+            ensure!(deposits > Decimal::ZERO, RadishError::PoolEmpty);
+
+            let plan: PositionPreview = self.plan_liquidate(&self.borrower(&borrower_id), deposits);
+
+            let seized: Vec<Bucket> = self.release_collateral(&borrower_id, &plan.released, &plan.collateral, plan.debt);
+            self.update_borrower(
+                &borrower_id,
+                plan.collateral.clone(),
+                plan.debt,
+                PositionAction::Liquidation {
+                    repaid: plan.repaid,
+                    seized: plan.released.clone(),
+                },
+            );
+
+            self.pool_vault.take(plan.repaid).burn();
+            self.total_debt = self.total_debt.checked_sub(plan.repaid).unwrap().max(Decimal::ZERO);
+            self.absorb(plan.repaid, deposits, seized);
+
+            Runtime::emit_event(LiquidateEvent {
+                position: borrower_id.clone(),
+                resource: self.radish_manager.address(),
+                repaid: plan.repaid,
+                seized: plan.released.clone(),
+                collateral: plan.collateral,
+                debt: plan.debt,
+                total_debt: self.total_debt,
+            });
+            Runtime::emit_event(PoolAbsorbEvent {
+                position: borrower_id,
+                absorbed: plan.repaid,
+                seized: plan.released,
+                total_deposits: self.pool_vault.amount(),
+                product: self.pool_product,
+                epoch: self.pool_epoch,
+                scale: self.pool_scale,
+            });
+        }
+
+        /* --------------- Preview Methods -------------- */
+        // Side-effect free, each returns the plan its action is carried out with, so previews never drift from the
+        // real outcome. Interest is projected to the current time without being checkpointed
+
+        /// Previews opening a position against the collateral
+        pub fn preview_supply(&self, collateral: AddrToAmount) -> PositionPreview {
+            self.plan_supply(&collateral)
+        }
+
+        /// Previews repaying RSH to withdraw collateral from a position
+        pub fn preview_withdraw(&self, borrower_id: NonFungibleLocalId, repayment: Decimal) -> PositionPreview {
+            let plan: RepayPlan = self.plan_repay(&self.borrower(&borrower_id), repayment);
+            self.repay_preview(&plan)
+        }
+
+        /// Previews borrowing more RSH against a position's collateral
+        pub fn preview_borrow_more(&self, borrower_id: NonFungibleLocalId, amount: Decimal) -> PositionPreview {
+            self.plan_borrow_more(&self.borrower(&borrower_id), amount)
+        }
+
+        /// Previews depositing more collateral into a position
+        pub fn preview_add_collateral(&self, borrower_id: NonFungibleLocalId, collateral: AddrToAmount) -> PositionPreview {
+            self.plan_add_collateral(&self.borrower(&borrower_id), &collateral)
+        }
+
+        /// Previews withdrawing collateral from a position without repaying
+        pub fn preview_withdraw_collateral(&self, borrower_id: NonFungibleLocalId, asset: ResourceAddress, amount: Decimal) -> PositionPreview {
+            self.plan_withdraw_collateral(&self.borrower(&borrower_id), asset, amount)
+        }
+
+        /// Previews repaying RSH to withdraw a single chosen collateral asset
+        pub fn preview_repay_targeted(&self, borrower_id: NonFungibleLocalId, repayment: Decimal, asset: ResourceAddress) -> PositionPreview {
+            self.plan_repay_targeted(&self.borrower(&borrower_id), repayment, asset)
+        }
+
+        /// Previews repaying RSH on behalf of a position
+        pub fn preview_repay_for(&self, borrower_id: NonFungibleLocalId, repayment: Decimal) -> PositionPreview {
+            self.plan_repay_for(&self.borrower(&borrower_id), repayment)
+        }
+
+        /// Previews liquidating a position, `released` being the seized collateral
+        pub fn preview_liquidate(&self, borrower_id: NonFungibleLocalId, repayment: Decimal) -> PositionPreview {
+            self.plan_liquidate(&self.borrower(&borrower_id), repayment)
+        }
+
+        /// Previews redeeming the given amount of RSH
+        pub fn preview_redeem(&self, redemption: Decimal) -> RedemptionPlan {
+            self.plan_redeem(redemption)
+        }
+
+        /// Previews merging positions into the first one
+        pub fn preview_merge_positions(&self, borrower_ids: Vec<NonFungibleLocalId>) -> PositionPreview {
+            let borrowers: Vec<Borrower> = borrower_ids.iter().map(|borrower_id| self.borrower(borrower_id)).collect();
+            self.plan_merge(&borrowers)
+        }
+
+        /// Previews splitting a position, returning the kept and the new position
+        pub fn preview_split_position(&self, borrower_id: NonFungibleLocalId, fraction: Decimal) -> (PositionPreview, PositionPreview) {
+            self.plan_split(&self.borrower(&borrower_id), fraction)
+        }
+
+        /// Previews moving collateral between two positions, returning the source then the destination
+        pub fn preview_rebalance(
+            &self,
+            from: NonFungibleLocalId,
+            to: NonFungibleLocalId,
+            asset: ResourceAddress,
+            amount: Decimal,
+        ) -> (PositionPreview, PositionPreview) {
+            self.plan_rebalance(&from, &to, asset, amount)
+        }
+
+        /* ---------------- Read Methods ---------------- */
+        /// Returns per-asset and protocol-wide aggregate state
+        ///
+        /// ! Totals are maintained as positions change, only the (short) asset list is walked here
+        pub fn get_protocol_stats(&self) -> ProtocolStats {
+            let radish: ResourceAddress = self.radish_manager.address();
+            let borrow_rate: Decimal = self.parameters.borrow_rate;
+            let total_debt: Decimal = math::scale_debt(self.total_debt, self.current_index(), self.interest_index);
+            let debt_ceiling_remaining: Decimal = self.parameters.debt_ceiling.checked_sub(total_debt).unwrap().max(Decimal::ZERO);
+            let utilization: Decimal = math::ratio(total_debt, self.parameters.debt_ceiling);
+
+            let mut assets: Vec<AssetStats> = Vec::new();
+            let mut total_collateral_value: Decimal = Decimal::ZERO;
+            for (id, asset, _) in self.asset_list.range(dec!(0)..self.next_asset_id) {
+                let config: AssetConfig = self.asset_configs.get(&asset).unwrap().clone();
+                let price: Option<Decimal> = self.oracle_prices.get(&asset).map(|price| *price);
+
+                let stats: AssetStats = if asset == radish {
+                    AssetStats {
+                        asset,
+                        id,
+                        status: config.status,
+                        price,
+                        total_supplied: Decimal::ZERO,
+                        total_borrowed: total_debt,
+                        utilization,
+                        borrow_rate,
+                        supply_cap_remaining: Decimal::ZERO,
+                        borrow_cap_remaining: debt_ceiling_remaining,
+                    }
+                } else {
+                    let total_supplied: Decimal = self.supplied(asset);
+                    if let Some(price) = price {
+                        total_collateral_value = total_collateral_value
+                            .checked_add(math::collateral_value(total_supplied, price, Decimal::ONE))
+                            .unwrap();
+                    }
+
+                    AssetStats {
+                        asset,
+                        id,
+                        status: config.status,
+                        price,
+                        total_supplied,
+                        total_borrowed: Decimal::ZERO,
+                        utilization: math::ratio(total_supplied, config.risk.supply_cap),
+                        borrow_rate: Decimal::ZERO,
+                        supply_cap_remaining: config.risk.supply_cap.checked_sub(total_supplied).unwrap().max(Decimal::ZERO),
+                        borrow_cap_remaining: Decimal::ZERO,
+                    }
+                };
+                assets.push(stats);
+            }
+
+            ProtocolStats {
+                assets,
+                total_collateral_value,
+                total_debt,
+                debt_ceiling_remaining,
+                utilization,
+                borrow_rate,
+                interest_index: self.current_index(),
+                open_positions: self.open_positions,
+                paused: self.paused,
+            }
+        }
+
+        /// Returns up to `limit` assets from the asset list, starting at asset id `start`
+        pub fn list_assets(&self, start: Decimal, limit: u32) -> AssetPage {
+            let mut assets: Vec<AssetListing> = Vec::new();
+            // A zero limit lists nothing, but still points at the first asset from `start` on
+            let mut next: Option<Decimal> = match limit {
+                0 => self.asset_list.range(start..self.next_asset_id).next().map(|(id, _, _)| id),
+                _ => None,
+            };
+
+            for (id, asset, next_id) in self.asset_list.range(start..self.next_asset_id).take(limit as usize) {
+                let config: AssetConfig = self.asset_configs.get(&asset).unwrap().clone();
+                let resource_manager: ResourceManager = ResourceManager::from(asset);
+
+                assets.push(AssetListing {
+                    asset,
+                    id,
+                    status: config.status,
+                    risk: config.risk,
+                    price: self.oracle_prices.get(&asset).map(|price| *price),
+                    symbol: resource_manager.get_metadata::<&str, String>("symbol").ok().flatten(),
+                    divisibility: Self::divisibility(asset),
+                });
+                next = next_id;
+            }
+
+            AssetPage { assets, next }
+        }
+
+        /// Returns the health of the given positions, worst health factor first
+        pub fn get_health_many(&self, borrower_ids: Vec<NonFungibleLocalId>) -> Vec<PositionHealth> {
+            self.health_many(borrower_ids)
+        }
+
+        /// Refreshes the wallet display and liquidation index entry of a position, e.g. after a price change
+        pub fn refresh_position(&mut self, borrower_id: NonFungibleLocalId) {
+            let borrower: Borrower = self.borrower(&borrower_id);
+            let debt: Decimal = self.current_debt(&borrower);
+            self.update_display(&borrower_id, &borrower.collateral, debt);
+            self.update_index(&borrower_id, &borrower.collateral, debt);
+        }
+
+        /// Returns up to `limit` of the least healthy indebted positions, worst first, for keepers to liquidate
+        ///
+        /// ! Positions are indexed by their health factor as of their last change or refresh. At least `RISK_WINDOW` of
+        /// ! them are re-ranked by their live health, but a position pushed further down the index by a later price move is
+        /// ! only found once refreshed
+        pub fn get_riskiest_positions(&self, limit: u32) -> Vec<PositionHealth> {
+            let mut healths: Vec<PositionHealth> = self.riskiest((limit as usize).max(RISK_WINDOW));
+            healths.truncate(limit as usize);
+            healths
+        }
+
+        /// Returns the remaining RSH and the collateral gains of a stability pool deposit
+        pub fn get_pool_deposit(&self, depositor_id: NonFungibleLocalId) -> PoolDeposit {
+            self.pool_deposit(&self.depositor(&depositor_id))
+        }
+
+        /// Returns up to `limit` entries of a position's history, oldest first, starting at entry `cursor`
+        ///
+        /// ! Histories outlive their positions, the history of a closed position can still be read
+        pub fn get_position_history(&self, borrower_id: NonFungibleLocalId, cursor: u64, limit: u32) -> HistoryPage {
+            let Some(history) = self.history.get(&borrower_id) else {
+                return HistoryPage {
+                    entries: Vec::new(),
+                    next: None,
+                };
+            };
+
+            let end: u64 = cursor.saturating_add(limit as u64).min(history.length);
+            let entries: Vec<HistoryEntry> = (cursor..end)
+                .map(|index| history.entries.get(&index).unwrap().clone())
+                .collect();
+            let next: Option<u64> = if end < history.length { Some(end) } else { None };
+
+            HistoryPage { entries, next }
+        }
+
+        /// Returns the delegates of a position with their permissions, in the order they were first granted; none once the
+        /// holder that granted them no longer holds the position
+        pub fn get_delegates(&self, borrower_id: NonFungibleLocalId) -> Vec<(ResourceOrNonFungible, DelegatePermissions)> {
+            let Some(delegation) = self.delegates.get(&borrower_id).map(|delegation| delegation.clone()) else {
+                return Vec::new();
+            };
+            if !self.held_by(delegation.holder, &borrower_id) {
+                return Vec::new();
+            }
+            delegation.delegates.into_iter().collect()
+        }
+
+        /* -------------- Private Methods ------------- */
+        fn validate_risk_config(risk: &RiskConfig) {
+            let invalid = |field: &str, value: Decimal| RadishError::InvalidRiskConfig {
+                field: field.to_string(),
+                value,
+            };
+            // Loan to value must be within [0, liquidation threshold], and the threshold below 1
+            ensure!(
+                risk.loan_to_value >= Decimal::ZERO && risk.loan_to_value <= risk.liquidation_threshold,
+                invalid("loan_to_value", risk.loan_to_value)
+            );
+            ensure!(
+                risk.liquidation_threshold < Decimal::ONE,
+                invalid("liquidation_threshold", risk.liquidation_threshold)
+            );
+            ensure!(risk.liquidation_bonus >= Decimal::ZERO, invalid("liquidation_bonus", risk.liquidation_bonus));
+            ensure!(risk.supply_cap >= Decimal::ZERO, invalid("supply_cap", risk.supply_cap));
+        }
+
+        fn validate_debt_ceiling(&self, borrowed: Decimal) {
+            let total_debt: Decimal = math::scale_debt(self.total_debt, self.current_index(), self.interest_index)
+                .checked_add(borrowed)
+                .unwrap();
+            ensure!(
+                total_debt <= self.parameters.debt_ceiling,
+                RadishError::DebtCeilingExceeded {
+                    total_debt,
+                    debt_ceiling: self.parameters.debt_ceiling,
+                }
+            );
+        }
+
+        /// Checks a position's debt stays within the loan to value weighted capacity of its collateral
+        fn validate_capacity(plan: &PositionPreview) {
+            ensure!(
+                plan.debt <= plan.borrow_capacity,
+                RadishError::InsufficientCollateral {
+                    debt: plan.debt,
+                    borrow_capacity: plan.borrow_capacity,
+                }
+            );
+        }
+
+        /// Checks a position left with debt keeps the withdrawal health factor
+        fn validate_withdrawal_health(&self, plan: &PositionPreview) {
+            ensure!(
+                plan.health_factor >= self.parameters.withdrawal_health_factor,
+                RadishError::HealthBelowMargin {
+                    health_factor: plan.health_factor,
+                    minimum: self.parameters.withdrawal_health_factor,
+                }
+            );
+        }
+
+        /// Checks a merged or split position cannot be liquidated
+        fn validate_reshaped_health(plan: &PositionPreview) {
+            ensure!(
+                plan.health_factor >= Decimal::ONE,
+                RadishError::HealthBelowMargin {
+                    health_factor: plan.health_factor,
+                    minimum: Decimal::ONE,
+                }
+            );
+        }
+
+        /// Checks a repayment is a positive amount of RSH
+        fn validate_repayment(&self, repayment: &Bucket) {
+            ensure!(
+                repayment.resource_address() == self.radish_manager.address() && repayment.amount() > Decimal::ZERO,
+                RadishError::InvalidRepayment {
+                    resource: repayment.resource_address(),
+                    amount: repayment.amount(),
+                }
+            );
+        }
+
+        /// Checks every asset in the map is listed, active, priced and within its supply cap
+        fn validate_collateral(&self, collateral: &AddrToAmount) {
+            for (&asset, &amount) in collateral.iter() {
+                ensure!(asset != self.radish_manager.address(), RadishError::RadishAsCollateral);
+                let config = self
+                    .asset_configs
+                    .get(&asset)
+                    .unwrap_or_else(|| RadishError::AssetNotListed { asset }.raise());
+                ensure!(config.status == AssetStatus::Active, RadishError::AssetFrozen { asset });
+                ensure!(self.oracle_prices.get(&asset).is_some(), RadishError::PriceUnavailable { asset });
+                ensure!(amount >= Decimal::ZERO, RadishError::InvalidAmount { resource: asset, amount });
+
+                let supplied: Decimal = self.supplied(asset).checked_add(amount).unwrap();
+                ensure!(
+                    supplied <= config.risk.supply_cap,
+                    RadishError::SupplyCapExceeded {
+                        asset,
+                        supplied,
+                        supply_cap: config.risk.supply_cap,
+                    }
+                );
+            }
+        }
+
+        fn borrower(&self, borrower_id: &NonFungibleLocalId) -> Borrower {
+            ensure!(
+                self.borrower_manager.non_fungible_exists(borrower_id),
+                RadishError::PositionNotFound {
+                    position: borrower_id.clone(),
+                }
+            );
+            self.borrower_manager.get_non_fungible_data::<Borrower>(borrower_id)
+        }
+
+        /// Local id of the single position a proof of borrower badges is for
+        fn position_id(&self, position_proof: NonFungibleProof) -> NonFungibleLocalId {
+            let resource: ResourceAddress = position_proof.resource_address();
+            let ids: IndexSet<NonFungibleLocalId> = self.position_ids(position_proof);
+
+            let amount: Decimal = Decimal::from(ids.len());
+            ensure!(amount == Decimal::ONE, RadishError::InvalidBorrowerBadge { resource, amount });
+            ids.into_iter().next().unwrap()
+        }
+
+        /// Local ids of all positions a proof of borrower badges is for
+        fn position_ids(&self, positions_proof: NonFungibleProof) -> IndexSet<NonFungibleLocalId> {
+            let resource: ResourceAddress = positions_proof.resource_address();
+            positions_proof
+                .check_with_message(
+                    self.borrower_manager.address(),
+                    &RadishError::InvalidBorrowerBadge {
+                        resource,
+                        amount: Decimal::ONE,
+                    }
+                    .message(),
+                )
+                .non_fungible_local_ids()
+        }
+
+        fn depositor(&self, depositor_id: &NonFungibleLocalId) -> Depositor {
+            ensure!(
+                self.depositor_manager.non_fungible_exists(depositor_id),
+                RadishError::InvalidDepositorBadge {
+                    resource: self.depositor_manager.address(),
+                    amount: Decimal::ZERO,
+                }
+            );
+            self.depositor_manager.get_non_fungible_data::<Depositor>(depositor_id)
+        }
+
+        /// Local id of the single deposit a proof of depositor badges is for
+        fn depositor_id(&self, depositor_proof: NonFungibleProof) -> NonFungibleLocalId {
+            let resource: ResourceAddress = depositor_proof.resource_address();
+            let ids: IndexSet<NonFungibleLocalId> = depositor_proof
+                .check_with_message(
+                    self.depositor_manager.address(),
+                    &RadishError::InvalidDepositorBadge {
+                        resource,
+                        amount: Decimal::ONE,
+                    }
+                    .message(),
+                )
+                .non_fungible_local_ids();
+
+            let amount: Decimal = Decimal::from(ids.len());
+            ensure!(amount == Decimal::ONE, RadishError::InvalidDepositorBadge { resource, amount });
+            ids.into_iter().next().unwrap()
+        }
+
+        /// Checks the delegate was granted the permission on the position by its current holder, and that its badge is in
+        /// the caller's auth zone. Returns the holder
+        fn authorize_delegate(
+            &self,
+            borrower_id: &NonFungibleLocalId,
+            delegate: &ResourceOrNonFungible,
+            permitted: impl Fn(&DelegatePermissions) -> bool,
+        ) -> ComponentAddress {
+            let delegation: Option<Delegation> = self.delegates.get(borrower_id).map(|delegation| delegation.clone());
+            let granted: bool = delegation.as_ref().is_some_and(|delegation| {
+                delegation.delegates.get(delegate).is_some_and(permitted) && self.held_by(delegation.holder, borrower_id)
+            });
+            ensure!(
+                granted,
+                RadishError::DelegateNotAuthorized {
+                    position: borrower_id.clone(),
+                    delegate: delegate.clone(),
+                }
+            );
+            Runtime::assert_access_rule(rule!(require(delegate.clone())));
+            delegation.unwrap().holder
+        }
+
+        /// Whether the account holds the badge of the position
+        fn held_by(&self, holder: ComponentAddress, borrower_id: &NonFungibleLocalId) -> bool {
+            let account: Global<Account> = Global::from(holder);
+            account.has_non_fungible(self.borrower_manager.address(), borrower_id.clone())
+        }
+
+        /// Health of each position, sorted worst first; ties keep the given order
+        fn health_many(&self, borrower_ids: Vec<NonFungibleLocalId>) -> Vec<PositionHealth> {
+            let mut healths: Vec<PositionHealth> = borrower_ids
+                .into_iter()
+                .map(|borrower_id| {
+                    let borrower: Borrower = self.borrower(&borrower_id);
+                    let debt: Decimal = self.current_debt(&borrower);
+                    PositionHealth {
+                        health_factor: self.health_factor(&borrower.collateral, debt),
+                        position: borrower_id,
+                        collateral: borrower.collateral,
+                        debt,
+                    }
+                })
+                .collect();
+            healths.sort_by_key(|health| health.health_factor);
+            healths
+        }
+
+        /// Live health of the first `window` positions of the liquidation index, worst first
+        fn riskiest(&self, window: usize) -> Vec<PositionHealth> {
+            let borrower_ids: Vec<NonFungibleLocalId> = self
+                .liquidation_index
+                .range(..)
+                .take(window)
+                .map(|((_, borrower_id), _, _)| borrower_id)
+                .collect();
+            self.health_many(borrower_ids)
+        }
+
+        /// Collateral with `amount` of an asset taken out, dropping the asset once none is left
+        fn without(collateral: &AddrToAmount, asset: ResourceAddress, amount: Decimal) -> AddrToAmount {
+            let mut collateral: AddrToAmount = collateral.clone();
+            let remaining: Decimal = collateral[&asset].checked_sub(amount).unwrap();
+            if remaining == Decimal::ZERO {
+                collateral.remove(&asset);
+            } else {
+                collateral.insert(asset, remaining);
+            }
+            collateral
+        }
+
+        /// Puts the buckets into the map, one bucket per resource
+        fn merge_buckets(merged: &mut HashMap<ResourceAddress, Bucket>, buckets: Vec<Bucket>) {
+            for bucket in buckets {
+                match merged.get_mut(&bucket.resource_address()) {
+                    Some(existing) => existing.put(bucket),
+                    None => {
+                        merged.insert(bucket.resource_address(), bucket);
+                    }
+                }
+            }
+        }
+
+        /// Totals the buckets by resource, merging duplicates
+        fn bucket_amounts(buckets: &[Bucket]) -> AddrToAmount {
+            let mut amounts: AddrToAmount = AddrToAmount::new();
+            for bucket in buckets.iter() {
+                let amount = amounts.entry(bucket.resource_address()).or_insert(Decimal::ZERO);
+                *amount = amount.checked_add(bucket.amount()).unwrap();
+            }
+            amounts
+        }
+
+        fn divisibility(asset: ResourceAddress) -> u8 {
+            match ResourceManager::from(asset).resource_type() {
+                ResourceType::Fungible { divisibility } => divisibility,
+                ResourceType::NonFungible { .. } => DIVISIBILITY_NONE,
+            }
+        }
+
+        /* ------------------- Plans ------------------ */
+        /// Resulting state of a position with the given collateral and debt, before any flows are filled in
+        fn preview(&self, collateral: AddrToAmount, debt: Decimal) -> PositionPreview {
+            PositionPreview {
+                health_factor: self.health_factor(&collateral, debt),
+                borrow_capacity: self.borrow_capacity(&collateral),
+                collateral,
+                debt,
+                borrowed: Decimal::ZERO,
+                repaid: Decimal::ZERO,
+                released: AddrToAmount::new(),
+                fees: AddrToAmount::new(),
+            }
+        }
+
+        /// Opening a position borrows the full capacity of its collateral
+        fn plan_supply(&self, collateral: &AddrToAmount) -> PositionPreview {
+            ensure!(!self.paused, RadishError::Paused);
+            ensure!(!collateral.is_empty(), RadishError::NoCollateral);
+            self.validate_collateral(collateral);
+
+            let borrowed: Decimal = self.borrow_capacity(collateral);
+            ensure!(borrowed > Decimal::ZERO, RadishError::CollateralWorthless);
+            self.validate_debt_ceiling(borrowed);
+
+            PositionPreview {
+                borrowed,
+                ..self.preview(collateral.clone(), borrowed)
+            }
+        }
+
+        /// Added collateral is allowed while paused, so positions can always be rescued
+        fn plan_add_collateral(&self, borrower: &Borrower, added: &AddrToAmount) -> PositionPreview {
+            ensure!(!added.is_empty(), RadishError::NoCollateral);
+            self.validate_collateral(added);
+
+            let mut collateral: AddrToAmount = borrower.collateral.clone();
+            for (&asset, &amount) in added.iter() {
+                let total = collateral.entry(asset).or_insert(Decimal::ZERO);
+                *total = total.checked_add(amount).unwrap();
+            }
+
+            self.preview(collateral, self.current_debt(borrower))
+        }
+
+        fn plan_borrow_more(&self, borrower: &Borrower, amount: Decimal) -> PositionPreview {
+            ensure!(!self.paused, RadishError::Paused);
+            ensure!(
+                amount > Decimal::ZERO,
+                RadishError::InvalidAmount {
+                    resource: self.radish_manager.address(),
+                    amount,
+                }
+            );
+            self.validate_debt_ceiling(amount);
+
+            let debt: Decimal = self.current_debt(borrower).checked_add(amount).unwrap();
+            let plan: PositionPreview = PositionPreview {
+                borrowed: amount,
+                ..self.preview(borrower.collateral.clone(), debt)
+            };
+            Self::validate_capacity(&plan);
+            plan
+        }
+
+        fn plan_withdraw_collateral(&self, borrower: &Borrower, asset: ResourceAddress, amount: Decimal) -> PositionPreview {
+            ensure!(!self.paused, RadishError::Paused);
+            let held: Decimal = borrower.collateral.get(&asset).copied().unwrap_or(Decimal::ZERO);
+            ensure!(
+                amount > Decimal::ZERO
+                    && amount <= held
+                    && amount == math::round_to_divisibility(amount, Self::divisibility(asset), math::ROUND_DOWN),
+                RadishError::InvalidAmount { resource: asset, amount }
+            );
+
+            let plan: PositionPreview = PositionPreview {
+                released: AddrToAmount::from([(asset, amount)]),
+                ..self.preview(Self::without(&borrower.collateral, asset, amount), self.current_debt(borrower))
+            };
+            self.validate_withdrawal_health(&plan);
+            plan
+        }
+
+        /// Releases the USD value the repaid share of the debt backs, all of it from one asset, rounded down
+        fn plan_repay_targeted(&self, borrower: &Borrower, repayment: Decimal, asset: ResourceAddress) -> PositionPreview {
+            ensure!(
+                repayment > Decimal::ZERO,
+                RadishError::InvalidRepayment {
+                    resource: self.radish_manager.address(),
+                    amount: repayment,
+                }
+            );
+            let held: Decimal = borrower.collateral.get(&asset).copied().unwrap_or(Decimal::ZERO);
+            ensure!(held > Decimal::ZERO, RadishError::InvalidAmount { resource: asset, amount: held });
+
+            // Collateral is released pro rata to the debt repaid, so a position without debt releases nothing
+            let debt: Decimal = self.current_debt(borrower);
+            ensure!(
+                debt > Decimal::ZERO,
+                RadishError::InvalidRepayment {
+                    resource: self.radish_manager.address(),
+                    amount: repayment,
+                }
+            );
+            let repaid: Decimal = repayment.min(debt);
+            let released_usd: Decimal = math::mul_div(self.collateral_value(&borrower.collateral), repaid, debt, math::ROUND_DOWN);
+            let amount: Decimal = math::round_to_divisibility(
+                math::mul_div(released_usd, Decimal::ONE, self.price(asset), math::ROUND_DOWN),
+                Self::divisibility(asset),
+                math::ROUND_DOWN,
+            )
+            .min(held);
+
+            let plan: PositionPreview = PositionPreview {
+                repaid,
+                released: AddrToAmount::from([(asset, amount)]),
+                ..self.preview(Self::without(&borrower.collateral, asset, amount), debt.checked_sub(repaid).unwrap())
+            };
+            self.validate_withdrawal_health(&plan);
+            plan
+        }
+
+        /// Repays up to the full debt, keeping all collateral in the position
+        fn plan_repay_for(&self, borrower: &Borrower, repayment: Decimal) -> PositionPreview {
+            ensure!(
+                repayment > Decimal::ZERO,
+                RadishError::InvalidRepayment {
+                    resource: self.radish_manager.address(),
+                    amount: repayment,
+                }
+            );
+            let debt: Decimal = self.current_debt(borrower);
+            let repaid: Decimal = repayment.min(debt);
+
+            PositionPreview {
+                repaid,
+                ..self.preview(borrower.collateral.clone(), debt.checked_sub(repaid).unwrap())
+            }
+        }
+
+        /// Repays at most the close factor of an unhealthy position's debt, seizing collateral pro rata to its value plus
+        /// each asset's own liquidation bonus
+        fn plan_liquidate(&self, borrower: &Borrower, repayment: Decimal) -> PositionPreview {
+            ensure!(!self.paused, RadishError::Paused);
+            let debt: Decimal = self.current_debt(borrower);
+            let health_factor: Decimal = self.health_factor(&borrower.collateral, debt);
+            ensure!(health_factor < Decimal::ONE, RadishError::PositionHealthy { health_factor });
+
+            let max_repay: Decimal = math::mul_div(debt, self.parameters.close_factor, Decimal::ONE, math::ROUND_DOWN);
+            let repaid: Decimal = repayment.min(max_repay);
+
+            let radish_price: Decimal = self.price(self.radish_manager.address());
+            let total_usd: Decimal = self.collateral_value(&borrower.collateral);
+            let mut seized: AddrToAmount = AddrToAmount::new();
+            let mut fees: AddrToAmount = AddrToAmount::new();
+            let mut collateral: AddrToAmount = AddrToAmount::new();
+            for (&address, &amount) in borrower.collateral.iter() {
+                let price: Decimal = self.price(address);
+                let bonus: Decimal = self.asset_configs.get(&address).unwrap().risk.liquidation_bonus;
+                let asset_usd: Decimal = math::collateral_value(amount, price, Decimal::ONE);
+                let seize: Decimal = math::round_to_divisibility(
+                    math::seize_amount(repaid, radish_price, asset_usd, total_usd, bonus, price, amount),
+                    Self::divisibility(address),
+                    math::ROUND_DOWN,
+                );
+
+                seized.insert(address, seize);
+                fees.insert(
+                    address,
+                    math::mul_div(seize, bonus, Decimal::ONE.checked_add(bonus).unwrap(), math::ROUND_DOWN),
+                );
+                collateral.insert(address, amount.checked_sub(seize).unwrap());
+            }
+
+            PositionPreview {
+                repaid,
+                released: seized,
+                fees,
+                ..self.preview(collateral, debt.checked_sub(repaid).unwrap())
+            }
+        }
+
+        fn plan_merge(&self, borrowers: &[Borrower]) -> PositionPreview {
+            ensure!(
+                borrowers.len() >= 2,
+                RadishError::InvalidBorrowerBadge {
+                    resource: self.borrower_manager.address(),
+                    amount: Decimal::from(borrowers.len()),
+                }
+            );
+
+            let mut collateral: AddrToAmount = AddrToAmount::new();
+            let mut debt: Decimal = Decimal::ZERO;
+            for borrower in borrowers.iter() {
+                for (&asset, &amount) in borrower.collateral.iter() {
+                    let total = collateral.entry(asset).or_insert(Decimal::ZERO);
+                    *total = total.checked_add(amount).unwrap();
+                }
+                debt = debt.checked_add(self.current_debt(borrower)).unwrap();
+            }
+
+            let plan: PositionPreview = self.preview(collateral, debt);
+            Self::validate_reshaped_health(&plan);
+            plan
+        }
+
+        /// Moved collateral is rounded down and moved debt up, so the totals are preserved exactly
+        fn plan_split(&self, borrower: &Borrower, fraction: Decimal) -> (PositionPreview, PositionPreview) {
+            ensure!(
+                fraction > Decimal::ZERO && fraction < Decimal::ONE,
+                RadishError::InvalidFraction { fraction }
+            );
+
+            let mut kept: AddrToAmount = AddrToAmount::new();
+            let mut moved: AddrToAmount = AddrToAmount::new();
+            for (&asset, &amount) in borrower.collateral.iter() {
+                let part: Decimal = math::round_to_divisibility(
+                    math::mul_div(amount, fraction, Decimal::ONE, math::ROUND_DOWN),
+                    Self::divisibility(asset),
+                    math::ROUND_DOWN,
+                );
+                kept.insert(asset, amount.checked_sub(part).unwrap());
+                moved.insert(asset, part);
+            }
+            let debt: Decimal = self.current_debt(borrower);
+            let moved_debt: Decimal = math::mul_div(debt, fraction, Decimal::ONE, math::ROUND_UP);
+
+            let kept: PositionPreview = self.preview(kept, debt.checked_sub(moved_debt).unwrap());
+            let moved: PositionPreview = self.preview(moved, moved_debt);
+            Self::validate_reshaped_health(&kept);
+            Self::validate_reshaped_health(&moved);
+            (kept, moved)
+        }
+
+        /// The source gives up the collateral as for a withdrawal, the destination takes it without a new deposit
+        fn plan_rebalance(
+            &self,
+            from: &NonFungibleLocalId,
+            to: &NonFungibleLocalId,
+            asset: ResourceAddress,
+            amount: Decimal,
+        ) -> (PositionPreview, PositionPreview) {
+            ensure!(from != to, RadishError::SamePosition { position: from.clone() });
+            let to: Borrower = self.borrower(to);
+
+            let source: PositionPreview = PositionPreview {
+                released: AddrToAmount::new(),
+                ..self.plan_withdraw_collateral(&self.borrower(from), asset, amount)
+            };
+
+            let mut collateral: AddrToAmount = to.collateral.clone();
+            let total = collateral.entry(asset).or_insert(Decimal::ZERO);
+            *total = total.checked_add(amount).unwrap();
+
+            (source, self.preview(collateral, self.current_debt(&to)))
+        }
+
+        /// Walks the riskiest positions from the lowest live health factor, repaying each position's debt in full until the
+        /// redemption runs out. Collateral is drawn pro rata to each asset's value, rounded down, and the fee is kept
+        /// from each drawn amount, rounded up
+        fn plan_redeem(&self, redemption: Decimal) -> RedemptionPlan {
+            ensure!(!self.paused, RadishError::Paused);
+            ensure!(
+                redemption > Decimal::ZERO,
+                RadishError::InvalidRepayment {
+                    resource: self.radish_manager.address(),
+                    amount: redemption,
+                }
+            );
+
+            // Debt repaid in each position
+            let mut remaining: Decimal = redemption;
+            let mut repayments: Vec<(PositionHealth, Decimal)> = Vec::new();
+            let candidates: Vec<PositionHealth> = self
+                .riskiest(RISK_WINDOW.max(MAX_REDEMPTION_POSITIONS))
+                .into_iter()
+                .filter(|health| health.health_factor >= Decimal::ONE)
+                .take(MAX_REDEMPTION_POSITIONS)
+                .collect();
+            for health in candidates {
+                if remaining == Decimal::ZERO {
+                    break;
+                }
+
+                let repaid: Decimal = remaining.min(health.debt);
+                remaining = remaining.checked_sub(repaid).unwrap();
+                repayments.push((health, repaid));
+            }
+            let redeemed: Decimal = redemption.checked_sub(remaining).unwrap();
+            ensure!(redeemed > Decimal::ZERO, RadishError::NothingToRedeem);
+
+            let now: i64 = Clock::current_time_rounded_to_seconds().seconds_since_unix_epoch;
+            let total_debt: Decimal = math::scale_debt(self.total_debt, self.current_index(), self.interest_index);
+            let base_rate: Decimal =
+                math::redemption_base_rate(math::decay_base_rate(self.base_rate, now - self.last_redemption), redeemed, total_debt);
+            let fee_rate: Decimal = self.parameters.redemption_fee_floor.checked_add(base_rate).unwrap().min(Decimal::ONE);
+
+            let radish_price: Decimal = self.price(self.radish_manager.address());
+            let mut plan: RedemptionPlan = RedemptionPlan {
+                redeemed,
+                fee_rate,
+                base_rate,
+                released: AddrToAmount::new(),
+                fees: AddrToAmount::new(),
+                positions: Vec::new(),
+            };
+            for (health, repaid) in repayments {
+                let redeemed_value: Decimal = math::mul_div(repaid, radish_price, Decimal::ONE, math::ROUND_DOWN);
+                let collateral_value: Decimal = self.collateral_value(&health.collateral);
+
+                let mut collateral: AddrToAmount = health.collateral.clone();
+                let mut released: AddrToAmount = AddrToAmount::new();
+                let mut fees: AddrToAmount = AddrToAmount::new();
+                for (&asset, &held) in health.collateral.iter() {
+                    let divisibility: u8 = Self::divisibility(asset);
+                    let drawn: Decimal = math::round_to_divisibility(
+                        math::mul_div(held, redeemed_value, collateral_value, math::ROUND_DOWN),
+                        divisibility,
+                        math::ROUND_DOWN,
+                    )
+                    .min(held);
+                    if drawn == Decimal::ZERO {
+                        continue;
+                    }
+                    let fee: Decimal =
+                        math::round_to_divisibility(math::mul_div(drawn, fee_rate, Decimal::ONE, math::ROUND_UP), divisibility, math::ROUND_UP)
+                            .min(drawn);
+
+                    collateral = Self::without(&collateral, asset, drawn);
+                    released.insert(asset, drawn.checked_sub(fee).unwrap());
+                    fees.insert(asset, fee);
+                    for (totals, amount) in [(&mut plan.released, drawn.checked_sub(fee).unwrap()), (&mut plan.fees, fee)] {
+                        let total = totals.entry(asset).or_insert(Decimal::ZERO);
+                        *total = total.checked_add(amount).unwrap();
+                    }
+                }
+
+                let position: PositionPreview = PositionPreview {
+                    repaid,
+                    released,
+                    fees,
+                    ..self.preview(collateral, health.debt.checked_sub(repaid).unwrap())
+                };
+                plan.positions.push((health.position, position));
+            }
+            plan
+        }
+
+        fn repay_preview(&self, plan: &RepayPlan) -> PositionPreview {
+            PositionPreview {
+                repaid: plan.repaid,
+                released: plan.released.clone(),
+                fees: plan.retained.clone(),
+                ..self.preview(plan.collateral.clone(), plan.debt)
+            }
+        }
+
+        /// Works out what repaying `repayment` RSH does to a position
+        ///
+        /// Payouts are rounded down to each resource's divisibility, with the rounding loss carried over to the next payout
+        /// ! A position left with less debt than the dust threshold has its remaining debt settled from its collateral, unless
+        /// it has no collateral left to settle it with
+        fn plan_repay(&self, borrower: &Borrower, repayment: Decimal) -> RepayPlan {
+            ensure!(
+                repayment > Decimal::ZERO,
+                RadishError::InvalidRepayment {
+                    resource: self.radish_manager.address(),
+                    amount: repayment,
+                }
+            );
+            let debt: Decimal = self.current_debt(borrower);
+
+            // If loan fully repaid with potential excess
+            if repayment >= debt {
+                return RepayPlan {
+                    repaid: debt,
+                    released: borrower.collateral.clone(),
+                    collateral: AddrToAmount::new(),
+                    remainders: AddrToAmount::new(),
+                    debt: Decimal::ZERO,
+                    retained: AddrToAmount::new(),
+                    settled: Decimal::ZERO,
+                };
+            }
+
+            let mut released: AddrToAmount = AddrToAmount::new();
+            let mut collateral: AddrToAmount = AddrToAmount::new();
+            let mut remainders: AddrToAmount = AddrToAmount::new();
+            for (&address, &amount) in borrower.collateral.iter() {
+                // The carried fraction is still part of the collateral, only the rest is shared out pro rata
+                let carried: Decimal = borrower.remainders.get(&address).copied().unwrap_or(Decimal::ZERO).min(amount);
+                let exact: Decimal = math::repay_release(amount.checked_sub(carried).unwrap(), repayment, debt)
+                    .checked_add(carried)
+                    .unwrap()
+                    .min(amount);
+                let payout: Decimal = math::round_to_divisibility(exact, Self::divisibility(address), math::ROUND_DOWN);
+
+                released.insert(address, payout);
+                collateral.insert(address, amount.checked_sub(payout).unwrap());
+                remainders.insert(address, exact.checked_sub(payout).unwrap());
+            }
+
+            let remaining_debt: Decimal = debt.checked_sub(repayment).unwrap();
+            // Sub-dust position, keep collateral worth the remaining debt and release the rest. Without any collateral to
+            // keep, the debt stays in the position rather than being written off
+            let retained: AddrToAmount = if remaining_debt < self.parameters.dust_threshold {
+                self.settle_dust(&collateral, remaining_debt)
+            } else {
+                AddrToAmount::new()
+            };
+            if retained.values().all(|kept| *kept == Decimal::ZERO) {
+                return RepayPlan {
+                    repaid: repayment,
+                    released,
+                    collateral,
+                    remainders,
+                    debt: remaining_debt,
+                    retained: AddrToAmount::new(),
+                    settled: Decimal::ZERO,
+                };
+            }
+
+            for (&address, &amount) in collateral.iter() {
+                let kept: Decimal = retained.get(&address).copied().unwrap_or(Decimal::ZERO);
+                let payout: Decimal = released.get(&address).copied().unwrap_or(Decimal::ZERO);
+                released.insert(address, payout.checked_add(amount).unwrap().checked_sub(kept).unwrap());
+            }
+
+            RepayPlan {
+                repaid: repayment,
+                released,
+                collateral: AddrToAmount::new(),
+                remainders: AddrToAmount::new(),
+                debt: Decimal::ZERO,
+                retained,
+                settled: remaining_debt,
+            }
+        }
+
+        /// Collateral retained to settle a dust debt, taken asset by asset and rounded up in the protocol's favour
+        ///
+        /// ! If the collateral is worth less than the debt, the difference is written off
+        fn settle_dust(&self, collateral: &AddrToAmount, debt: Decimal) -> AddrToAmount {
+            let mut owed: Decimal = math::mul_div(debt, self.price(self.radish_manager.address()), Decimal::ONE, math::ROUND_UP);
+            let mut retained: AddrToAmount = AddrToAmount::new();
+
+            for (&address, &amount) in collateral.iter() {
+                if owed == Decimal::ZERO {
+                    break;
+                }
+
+                let price: Decimal = self.price(address);
+                let kept: Decimal = math::round_to_divisibility(
+                    math::mul_div(owed, Decimal::ONE, price, math::ROUND_UP),
+                    Self::divisibility(address),
+                    math::ROUND_UP,
+                )
+                .min(amount);
+
+                owed = owed
+                    .checked_sub(math::collateral_value(kept, price, Decimal::ONE))
+                    .unwrap()
+                    .max(Decimal::ZERO);
+                retained.insert(address, kept);
+            }
+            retained
+        }
+
+        /// Collateral held for an asset on behalf of positions
+        fn supplied(&self, asset: ResourceAddress) -> Decimal {
+            self.total_supplied.get(&asset).map(|amount| *amount).unwrap_or(Decimal::ZERO)
+        }
+
+        /// Adjusts the collateral held for an asset by `delta`, returning the new total
+        fn update_total_supplied(&mut self, asset: ResourceAddress, delta: Decimal) -> Decimal {
+            let total: Decimal = self.supplied(asset).checked_add(delta).unwrap();
+            self.total_supplied.insert(asset, total);
+            total
+        }
+
+        /// Adjusts the collateral retained by the protocol for an asset by `delta`
+        fn update_reserves(&mut self, asset: ResourceAddress, delta: Decimal) {
+            let total: Decimal = self
+                .reserves
+                .get(&asset)
+                .map(|amount| *amount)
+                .unwrap_or(Decimal::ZERO)
+                .checked_add(delta)
+                .unwrap();
+            self.reserves.insert(asset, total);
+        }
+
+        fn price(&self, asset: ResourceAddress) -> Decimal {
+            *self
+                .oracle_prices
+                .get(&asset)
+                .unwrap_or_else(|| RadishError::PriceUnavailable { asset }.raise())
+        }
+
+        /// USD value of the collateral
+        fn collateral_value(&self, collateral: &AddrToAmount) -> Decimal {
+            self.weighted_value(collateral, |_| Decimal::ONE)
+        }
+
+        /// RSH which may be borrowed against the collateral
+        fn borrow_capacity(&self, collateral: &AddrToAmount) -> Decimal {
+            let value: Decimal = self.weighted_value(collateral, |risk| risk.loan_to_value);
+            math::borrow_capacity(value, self.price(self.radish_manager.address()))
+        }
+
+        /// Threshold-weighted collateral value over debt value; a position is liquidatable below 1
+        fn health_factor(&self, collateral: &AddrToAmount, debt: Decimal) -> Decimal {
+            let value: Decimal = self.weighted_value(collateral, |risk| risk.liquidation_threshold);
+            math::health_factor(value, debt, self.price(self.radish_manager.address()))
+        }
+
+        /// USD value of the collateral, each asset weighted by a factor of its risk configuration
+        fn weighted_value(&self, collateral: &AddrToAmount, weight: impl Fn(&RiskConfig) -> Decimal) -> Decimal {
+            let mut value: Decimal = Decimal::ZERO;
+            for (&address, &amount) in collateral.iter() {
+                let factor: Decimal = weight(&self.asset_configs.get(&address).unwrap().risk);
+                value = value
+                    .checked_add(math::collateral_value(amount, self.price(address), factor))
+                    .unwrap();
+            }
+            value
+        }
+
+        /// Interest index as of the current time, without checkpointing it
+        fn current_index(&self) -> Decimal {
+            let now: i64 = Clock::current_time_rounded_to_seconds().seconds_since_unix_epoch;
+            math::accrue_index(
+                self.interest_index,
+                self.parameters.borrow_rate,
+                now - self.last_accrual,
+                SECONDS_PER_YEAR,
+            )
+        }
+
+        /// Live debt of a position, including interest accrued since its last checkpoint
+        fn current_debt(&self, borrower: &Borrower) -> Decimal {
+            math::scale_debt(borrower.debt, self.current_index(), borrower.debt_index)
+        }
+
+        /// Checkpoints a position at the current interest index, recording the action in its history
+        fn update_borrower(&mut self, borrower_id: &NonFungibleLocalId, collateral: AddrToAmount, debt: Decimal, action: PositionAction) {
+            // Interest accrued since the previous checkpoint gets its own entry
+            let previous: Borrower = self.borrower(borrower_id);
+            let previous_debt: Decimal = self.current_debt(&previous);
+            let interest: Decimal = previous_debt.checked_sub(previous.debt).unwrap();
+            if interest > Decimal::ZERO {
+                self.record(borrower_id, PositionAction::Accrual { interest }, previous.collateral, previous_debt);
+            }
+
+            self.update_display(borrower_id, &collateral, debt);
+            self.update_index(borrower_id, &collateral, debt);
+            self.borrower_manager
+                .update_non_fungible_data(borrower_id, "collateral", collateral.clone());
+            self.borrower_manager.update_non_fungible_data(borrower_id, "debt", debt);
+            self.borrower_manager
+                .update_non_fungible_data(borrower_id, "debt_index", self.interest_index);
+            // Remainders only hold for the collateral they were rounded from, callers that keep them track them themselves
+            if !previous.remainders.is_empty() {
+                self.borrower_manager
+                    .update_non_fungible_data(borrower_id, "remainders", AddrToAmount::new());
+            }
+            self.record(borrower_id, action, collateral, debt);
+        }
+
+        /// Re-keys a position in the liquidation index under its current health factor, leaving debt free positions out
+        fn update_index(&mut self, borrower_id: &NonFungibleLocalId, collateral: &AddrToAmount, debt: Decimal) {
+            if let Some(health_factor) = self.index_keys.remove(borrower_id) {
+                self.liquidation_index.remove(&(health_factor, borrower_id.clone()));
+            }
+            if debt == Decimal::ZERO {
+                return;
+            }
+
+            let health_factor: Decimal = self.health_factor(collateral, debt);
+            self.liquidation_index.insert((health_factor, borrower_id.clone()), ());
+            self.index_keys.insert(borrower_id.clone(), health_factor);
+        }
+
+        /// Appends an action to the history of a position, with the collateral and debt it left behind
+        fn record(&mut self, borrower_id: &NonFungibleLocalId, action: PositionAction, collateral: AddrToAmount, debt: Decimal) {
+            let entry: HistoryEntry = HistoryEntry {
+                timestamp: Clock::current_time_rounded_to_seconds().seconds_since_unix_epoch,
+                action,
+                collateral,
+                debt,
+            };
+
+            if let Some(mut history) = self.history.get_mut(borrower_id) {
+                let index: u64 = history.length;
+                history.entries.insert(index, entry);
+                history.length += 1;
+                return;
+            }
+
+            let entries: KeyValueStore<u64, HistoryEntry> = KeyValueStore::new();
+            entries.insert(0, entry);
+            self.history
+                .insert(borrower_id.clone(), PositionHistory { entries, length: 1 });
+        }
+
+        /// Data of a newly minted position, checkpointed at the current interest index
+        fn new_borrower(&self, collateral: AddrToAmount, debt: Decimal) -> Borrower {
+            let (description, key_image_url, health_tier) = self.display(&collateral, debt);
+            Borrower {
+                collateral,
+                debt,
+                debt_index: self.interest_index,
+                remainders: AddrToAmount::new(),
+                description,
+                key_image_url,
+                health_tier,
+            }
+        }
+
+        fn update_display(&self, borrower_id: &NonFungibleLocalId, collateral: &AddrToAmount, debt: Decimal) {
+            let (description, key_image_url, health_tier) = self.display(collateral, debt);
+            self.borrower_manager
+                .update_non_fungible_data(borrower_id, "description", description);
+            self.borrower_manager
+                .update_non_fungible_data(borrower_id, "key_image_url", key_image_url);
+            self.borrower_manager
+                .update_non_fungible_data(borrower_id, "health_tier", health_tier);
+        }
+
+        /// Wallet description, SVG image and health tier of a position, collateral listed by symbol
+        fn display(&self, collateral: &AddrToAmount, debt: Decimal) -> (String, Url, HealthTier) {
+            let mut assets: Vec<DisplayAsset> = collateral
+                .iter()
+                .filter(|(_, amount)| **amount > Decimal::ZERO)
+                .map(|(&asset, &amount)| DisplayAsset {
+                    symbol: Self::symbol(asset),
+                    amount,
+                    value: math::mul_div(amount, self.price(asset), Decimal::ONE, math::ROUND_DOWN),
+                })
+                .collect();
+            assets.sort();
+
+            let health_factor: Decimal = self.health_factor(collateral, debt);
+            let tier: HealthTier = display::health_tier(health_factor, debt, self.parameters.withdrawal_health_factor);
+            (
+                display::description(&assets, debt, health_factor, tier),
+                display::image_url(&assets, debt, health_factor, tier),
+                tier,
+            )
+        }
+
+        /// Symbol of an asset from its metadata, its address if it has none
+        fn symbol(asset: ResourceAddress) -> String {
+            ResourceManager::from(asset)
+                .get_metadata::<&str, String>("symbol")
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| Runtime::bech32_encode_address(asset))
+        }
+
+        /* --------------- Stability Pool -------------- */
+        /// Sums of the pool at an epoch and scale, empty until something is absorbed there
+        fn pool_sums(&self, epoch: u64, scale: u64) -> AddrToAmount {
+            self.pool_sums
+                .get(&(epoch, scale))
+                .map(|sums| sums.clone())
+                .unwrap_or_default()
+        }
+
+        /// Data of a deposit of `deposit` snapshotting the pool as it is now
+        fn new_depositor(&self, deposit: Decimal) -> Depositor {
+            Depositor {
+                deposit,
+                product: self.pool_product,
+                sums: self.pool_sums(self.pool_epoch, self.pool_scale),
+                epoch: self.pool_epoch,
+                scale: self.pool_scale,
+            }
+        }
+
+        fn update_depositor(&self, depositor_id: &NonFungibleLocalId, depositor: Depositor) {
+            self.depositor_manager
+                .update_non_fungible_data(depositor_id, "deposit", depositor.deposit);
+            self.depositor_manager
+                .update_non_fungible_data(depositor_id, "product", depositor.product);
+            self.depositor_manager
+                .update_non_fungible_data(depositor_id, "sums", depositor.sums);
+            self.depositor_manager
+                .update_non_fungible_data(depositor_id, "epoch", depositor.epoch);
+            self.depositor_manager
+                .update_non_fungible_data(depositor_id, "scale", depositor.scale);
+        }
+
+        /// Remaining RSH and collateral gains of a deposit, gains rounded down to each asset's divisibility
+        ///
+        /// ! Deposits snapshotted before the pool was last emptied are worth nothing, but keep their gains
+        fn pool_deposit(&self, depositor: &Depositor) -> PoolDeposit {
+            let deposit: Decimal = if depositor.epoch == self.pool_epoch {
+                math::compounded_deposit(depositor.deposit, depositor.product, self.pool_product, self.pool_scale - depositor.scale)
+            } else {
+                Decimal::ZERO
+            };
+
+            // Gains made at the snapshot's scale and, with its product shrunk by the scale factor, the one after
+            let sums: AddrToAmount = self.pool_sums(depositor.epoch, depositor.scale);
+            let next_sums: AddrToAmount = self.pool_sums(depositor.epoch, depositor.scale + 1);
+            let mut gains: AddrToAmount = AddrToAmount::new();
+            for &asset in sums.keys().chain(next_sums.keys()) {
+                let gain: Decimal = math::round_to_divisibility(
+                    math::pool_gain(
+                        depositor.deposit,
+                        depositor.product,
+                        depositor.sums.get(&asset).copied().unwrap_or(Decimal::ZERO),
+                        sums.get(&asset).copied().unwrap_or(Decimal::ZERO),
+                        next_sums.get(&asset).copied().unwrap_or(Decimal::ZERO),
+                    ),
+                    Self::divisibility(asset),
+                    math::ROUND_DOWN,
+                );
+                if gain > Decimal::ZERO {
+                    gains.insert(asset, gain);
+                }
+            }
+
+            PoolDeposit { deposit, gains }
+        }
+
+        /// Shares collateral seized for `absorbed` RSH among `deposits`, then shrinks every deposit by the absorbed share
+        fn absorb(&mut self, absorbed: Decimal, deposits: Decimal, seized: Vec<Bucket>) {
+            let mut sums: AddrToAmount = self.pool_sums(self.pool_epoch, self.pool_scale);
+            for bucket in seized {
+                let asset: ResourceAddress = bucket.resource_address();
+                let gained: Decimal = math::mul_div(bucket.amount(), self.pool_product, deposits, math::ROUND_DOWN);
+                let sum = sums.entry(asset).or_insert(Decimal::ZERO);
+                *sum = sum.checked_add(gained).unwrap();
+
+                if self.pool_gains.get(&asset).is_none() {
+                    self.pool_gains.insert(asset, Vault::new(asset));
+                }
+                self.pool_gains.get_mut(&asset).unwrap().put(bucket);
+            }
+            self.pool_sums.insert((self.pool_epoch, self.pool_scale), sums);
+
+            // Emptied, or left with so little that every deposit rounds to nothing
+            let (product, rescaled) = math::pool_product(self.pool_product, absorbed, deposits);
+            if product == Decimal::ZERO {
+                self.pool_epoch += 1;
+                self.pool_scale = 0;
+                self.pool_product = Decimal::ONE;
+            } else {
+                if rescaled {
+                    self.pool_scale += 1;
+                }
+                self.pool_product = product;
+            }
+        }
+
+        /// Repays RSH into a position, releasing collateral as planned by `plan_repay`
+        fn repay(&mut self, borrower_id: NonFungibleLocalId, mut repayment: Bucket) -> Vec<Bucket> {
+            let borrower_data: Borrower = self.borrower(&borrower_id);
+            let plan: RepayPlan = self.plan_repay(&borrower_data, repayment.amount());
+
+            let mut released: Vec<Bucket> = self.release_collateral(&borrower_id, &plan.released, &plan.collateral, plan.debt);
+            for (&address, &amount) in plan.retained.iter() {
+                self.update_total_supplied(address, -amount);
+                self.update_reserves(address, amount);
+            }
+
+            repayment.take(plan.repaid).burn();
+            self.total_debt = self
+                .total_debt
+                .checked_sub(plan.repaid.checked_add(plan.settled).unwrap())
+                .unwrap()
+                .max(Decimal::ZERO);
+
+            self.update_borrower(
+                &borrower_id,
+                plan.collateral.clone(),
+                plan.debt,
+                PositionAction::Repay {
+                    repaid: plan.repaid,
+                    released: plan.released.clone(),
+                },
+            );
+            self.borrower_manager
+                .update_non_fungible_data(&borrower_id, "remainders", plan.remainders);
+
+            if repayment.is_empty() {
+                repayment.drop_empty();
+            } else {
+                released.push(repayment);
+            }
+
+            Runtime::emit_event(RepayEvent {
+                position: borrower_id,
+                resource: self.radish_manager.address(),
+                amount: plan.repaid,
+                collateral: plan.collateral,
+                debt: plan.debt,
+                total_debt: self.total_debt,
+            });
+
+            released
+        }
+
+        /// Puts the buckets into the vaults, emitting a supply event for each
+        fn deposit_collateral(
+            &mut self,
+            borrower_id: &NonFungibleLocalId,
+            buckets: Vec<Bucket>,
+            new_collateral: &AddrToAmount,
+            new_debt: Decimal,
+        ) {
+            for bucket in buckets {
+                let address: ResourceAddress = bucket.resource_address();
+                let amount: Decimal = bucket.amount();
+                self.vaults.get_mut(&address).unwrap().put(bucket);
+                let total_supplied: Decimal = self.update_total_supplied(address, amount);
+
+                Runtime::emit_event(SupplyEvent {
+                    position: borrower_id.clone(),
+                    asset: address,
+                    amount,
+                    collateral: new_collateral.clone(),
+                    debt: new_debt,
+                    total_supplied,
+                });
+            }
+        }
+
+        /// Takes the given collateral out of the vaults, emitting a withdraw event for each asset
+        fn release_collateral(
+            &mut self,
+            borrower_id: &NonFungibleLocalId,
+            released_collateral: &AddrToAmount,
+            new_collateral: &AddrToAmount,
+            new_debt: Decimal,
+        ) -> Vec<Bucket> {
+            let mut released: Vec<Bucket> = Vec::new();
+            for (&address, &amount) in released_collateral.iter() {
+                // Payouts rounded down to nothing stay in the position
+                if amount == Decimal::ZERO {
+                    continue;
+                }
+                let mut vault = self
+                    .vaults
+                    .get_mut(&address)
+                    .unwrap_or_else(|| RadishError::AssetNotListed { asset: address }.raise());
+                ensure!(
+                    vault.amount() >= amount,
+                    RadishError::InsufficientVault {
+                        asset: address,
+                        requested: amount,
+                        available: vault.amount(),
+                    }
+                );
+
+                released.push(vault.take(amount));
+                drop(vault);
+                let total_supplied: Decimal = self.update_total_supplied(address, -amount);
+
+                Runtime::emit_event(WithdrawEvent {
+                    position: borrower_id.clone(),
+                    asset: address,
+                    amount,
+                    collateral: new_collateral.clone(),
+                    debt: new_debt,
+                    total_supplied,
+                });
+            }
+            released
         }
     }
 }
@@ -0,0 +1,271 @@
+/* ------------------ Imports ----------------- */
+use scrypto::prelude::*;
+
+/* ------------------ Rounding ---------------- */
+// Every function rounds in the protocol's favour: values credited to users (collateral value, borrow capacity, released
+// or seized collateral) are rounded down, values owed by users (debt, interest) are rounded up.
+
+/// Rounds values credited to users
+pub const ROUND_DOWN: RoundingMode = RoundingMode::ToNegativeInfinity;
+/// Rounds values owed by users
+pub const ROUND_UP: RoundingMode = RoundingMode::ToPositiveInfinity;
+
+/// `a * b / c`, computed at `PreciseDecimal` precision and rounded once at the end
+pub fn mul_div(a: Decimal, b: Decimal, c: Decimal, mode: RoundingMode) -> Decimal {
+    assert!(c != Decimal::ZERO, "Division by zero");
+
+    let result: PreciseDecimal = PreciseDecimal::from(a)
+        .checked_mul(PreciseDecimal::from(b))
+        .unwrap()
+        .checked_div(PreciseDecimal::from(c))
+        .unwrap()
+        .checked_round(Decimal::SCALE as i32, mode)
+        .unwrap();
+
+    Decimal::try_from(result).unwrap()
+}
+
+/// Rounds an amount to the divisibility of its resource
+pub fn round_to_divisibility(amount: Decimal, divisibility: u8, mode: RoundingMode) -> Decimal {
+    amount.checked_round(divisibility as i32, mode).unwrap()
+}
+
+/// `part / whole`, or zero if `whole` is zero
+pub fn ratio(part: Decimal, whole: Decimal) -> Decimal {
+    if whole == Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    mul_div(part, Decimal::ONE, whole, ROUND_DOWN)
+}
+
+/* ----------------- Valuation ---------------- */
+/// USD value of an amount of collateral, optionally weighted by a risk factor (loan to value, liquidation threshold)
+pub fn collateral_value(amount: Decimal, price: Decimal, weight: Decimal) -> Decimal {
+    mul_div(amount.checked_mul(price).unwrap(), weight, Decimal::ONE, ROUND_DOWN)
+}
+
+/// RSH which may be borrowed against collateral of the given (loan to value weighted) USD value
+pub fn borrow_capacity(weighted_value: Decimal, radish_price: Decimal) -> Decimal {
+    mul_div(weighted_value, Decimal::ONE, radish_price, ROUND_DOWN)
+}
+
+/// Threshold-weighted collateral value over debt value; a position is liquidatable below 1
+pub fn health_factor(weighted_value: Decimal, debt: Decimal, radish_price: Decimal) -> Decimal {
+    if debt == Decimal::ZERO {
+        return Decimal::MAX;
+    }
+    let debt_value: Decimal = mul_div(debt, radish_price, Decimal::ONE, ROUND_UP);
+    mul_div(weighted_value, Decimal::ONE, debt_value, ROUND_DOWN)
+}
+
+/* ------------------ Interest ---------------- */
+/// Interest index after `elapsed` seconds at the annual `rate`, compounding once per accrual
+pub fn accrue_index(index: Decimal, rate: Decimal, elapsed: i64, seconds_per_year: i64) -> Decimal {
+    if elapsed <= 0 {
+        return index;
+    }
+    let growth: Decimal = mul_div(rate, Decimal::from(elapsed), Decimal::from(seconds_per_year), ROUND_UP);
+    mul_div(index, Decimal::ONE.checked_add(growth).unwrap(), Decimal::ONE, ROUND_UP)
+}
+
+/// Debt checkpointed at `from_index`, as of `to_index`
+pub fn scale_debt(debt: Decimal, to_index: Decimal, from_index: Decimal) -> Decimal {
+    mul_div(debt, to_index, from_index, ROUND_UP)
+}
+
+/* ----------------- Repayment ---------------- */
+/// Collateral released by repaying `repayment` of `debt`, pro rata to the repaid share
+pub fn repay_release(collateral: Decimal, repayment: Decimal, debt: Decimal) -> Decimal {
+    if repayment >= debt {
+        return collateral;
+    }
+    mul_div(collateral, repayment, debt, ROUND_DOWN)
+}
+
+/* ---------------- Liquidation --------------- */
+/// Collateral seized from one asset of a position, for its share (`asset_value` of `total_value`) of the repaid debt
+pub fn seize_amount(
+    repaid: Decimal,
+    radish_price: Decimal,
+    asset_value: Decimal,
+    total_value: Decimal,
+    bonus: Decimal,
+    price: Decimal,
+    available: Decimal,
+) -> Decimal {
+    if total_value == Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+
+    let repaid_value: Decimal = mul_div(repaid, radish_price, Decimal::ONE, ROUND_DOWN);
+    let share_value: Decimal = mul_div(repaid_value, asset_value, total_value, ROUND_DOWN);
+    let bonus_value: Decimal = mul_div(share_value, Decimal::ONE.checked_add(bonus).unwrap(), Decimal::ONE, ROUND_DOWN);
+
+    mul_div(bonus_value, Decimal::ONE, price, ROUND_DOWN).min(available)
+}
+
+/* ---------------- Redemption ---------------- */
+// The redemption base rate jumps with every redemption and decays over time, so a run of redemptions gets ever more
+// expensive while occasional ones stay near the fee floor
+
+/// Decay of the base rate per elapsed minute, halving it every 12 hours
+pub const BASE_RATE_DECAY_PER_MINUTE: Decimal = dec!("0.999037758833783");
+/// Redeeming this share of the total debt adds half of it to the base rate
+pub const REDEMPTION_BETA: Decimal = dec!(2);
+/// Minutes after which the base rate is treated as fully decayed
+pub const MAX_DECAY_MINUTES: i64 = 525_600;
+
+/// Base rate after `elapsed` seconds without redemptions
+pub fn decay_base_rate(base_rate: Decimal, elapsed: i64) -> Decimal {
+    let minutes: i64 = elapsed / 60;
+    if minutes <= 0 {
+        return base_rate;
+    }
+    if minutes >= MAX_DECAY_MINUTES {
+        return Decimal::ZERO;
+    }
+
+    let decay: Decimal = BASE_RATE_DECAY_PER_MINUTE.checked_powi(minutes).unwrap();
+    mul_div(base_rate, decay, Decimal::ONE, ROUND_DOWN)
+}
+
+/// Base rate after redeeming `redeemed` out of `total_debt`, starting from the decayed base rate, at most 100%
+pub fn redemption_base_rate(decayed: Decimal, redeemed: Decimal, total_debt: Decimal) -> Decimal {
+    if total_debt == Decimal::ZERO {
+        return Decimal::ONE;
+    }
+    let added: Decimal = mul_div(redeemed, Decimal::ONE, total_debt.checked_mul(REDEMPTION_BETA).unwrap(), ROUND_UP);
+    decayed.checked_add(added).unwrap().min(Decimal::ONE)
+}
+
+/* -------------- Stability Pool -------------- */
+// Every absorbed liquidation shrinks all deposits by the same factor and adds collateral pro rata to them, so a deposit
+// is valued from a snapshot of two running totals: the product P of those factors, and per asset the sum S of collateral
+// gained per unit deposited, weighted by P. P is multiplied by the scale factor whenever it gets too small to stay
+// precise, starting a new scale, and resets when the pool is emptied, starting a new epoch
+
+/// Factor P is multiplied by when it falls below its inverse
+pub const POOL_SCALE_FACTOR: Decimal = dec!(1_000_000_000);
+
+/// Product P after absorbing `absorbed` out of `deposits`, and whether a new scale was started. Zero once emptied
+pub fn pool_product(product: Decimal, absorbed: Decimal, deposits: Decimal) -> (Decimal, bool) {
+    let product: Decimal = mul_div(product, deposits.checked_sub(absorbed).unwrap(), deposits, ROUND_DOWN);
+    if product == Decimal::ZERO || mul_div(product, POOL_SCALE_FACTOR, Decimal::ONE, ROUND_DOWN) >= Decimal::ONE {
+        return (product, false);
+    }
+    (mul_div(product, POOL_SCALE_FACTOR, Decimal::ONE, ROUND_DOWN), true)
+}
+
+/// Deposit of `deposit` at product `snapshot`, now that the product is `product`, `scales` scales later
+pub fn compounded_deposit(deposit: Decimal, snapshot: Decimal, product: Decimal, scales: u64) -> Decimal {
+    match scales {
+        0 => mul_div(deposit, product, snapshot, ROUND_DOWN),
+        1 => mul_div(deposit, product, snapshot.checked_mul(POOL_SCALE_FACTOR).unwrap(), ROUND_DOWN),
+        // Shrunk by more than the scale factor squared, nothing worth counting is left
+        _ => Decimal::ZERO,
+    }
+}
+
+/// Collateral gained by `deposit` since its snapshot (`snapshot`, `snapshot_sum`), given the sum of the snapshot's scale
+/// now and the sum of the scale after it
+pub fn pool_gain(deposit: Decimal, snapshot: Decimal, snapshot_sum: Decimal, sum: Decimal, next_sum: Decimal) -> Decimal {
+    let gained: Decimal = sum
+        .checked_sub(snapshot_sum)
+        .unwrap()
+        .checked_add(mul_div(next_sum, Decimal::ONE, POOL_SCALE_FACTOR, ROUND_DOWN))
+        .unwrap();
+    mul_div(deposit, gained, snapshot, ROUND_DOWN)
+}
+
+/* ------------------- Tests ------------------ */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::SECONDS_PER_YEAR;
+
+    #[test]
+    fn test_mul_div_rounding() {
+        assert_eq!(mul_div(dec!(1), dec!(1), dec!(3), ROUND_DOWN), dec!("0.333333333333333333"));
+        assert_eq!(mul_div(dec!(1), dec!(1), dec!(3), ROUND_UP), dec!("0.333333333333333334"));
+        assert_eq!(mul_div(dec!(2), dec!(3), dec!(6), ROUND_UP), dec!(1));
+    }
+
+    #[test]
+    fn test_round_to_divisibility() {
+        assert_eq!(round_to_divisibility(dec!("1.999"), 2, ROUND_DOWN), dec!("1.99"));
+        assert_eq!(round_to_divisibility(dec!("1.991"), 2, ROUND_UP), dec!(2));
+        assert_eq!(round_to_divisibility(dec!("0.5"), 0, ROUND_DOWN), dec!(0));
+    }
+
+    #[test]
+    fn test_valuation() {
+        let value: Decimal = collateral_value(dec!(1000), dec!(2), dec!("0.5"));
+        assert_eq!(value, dec!(1000));
+        assert_eq!(borrow_capacity(value, dec!(2)), dec!(500));
+
+        assert_eq!(health_factor(dec!(650), dec!(500), dec!(1)), dec!("1.3"));
+        assert_eq!(health_factor(dec!(650), dec!(0), dec!(1)), Decimal::MAX);
+    }
+
+    #[test]
+    fn test_interest_rounds_up() {
+        let index: Decimal = accrue_index(dec!(1), dec!("0.05"), SECONDS_PER_YEAR, SECONDS_PER_YEAR);
+        assert_eq!(index, dec!("1.05"));
+        assert_eq!(accrue_index(index, dec!("0.05"), 0, SECONDS_PER_YEAR), index);
+
+        // A single second of interest on a tiny debt still costs the borrower something
+        let index: Decimal = accrue_index(dec!(1), dec!("0.05"), 1, SECONDS_PER_YEAR);
+        assert!(scale_debt(dec!("0.000000000000000001"), index, dec!(1)) > dec!("0.000000000000000001"));
+    }
+
+    #[test]
+    fn test_repay_release_rounds_down() {
+        assert_eq!(repay_release(dec!(100), dec!(1), dec!(3)), dec!("33.333333333333333333"));
+        assert_eq!(repay_release(dec!(100), dec!(5), dec!(3)), dec!(100));
+    }
+
+    #[test]
+    fn test_base_rate_decays_by_half_every_12_hours() {
+        let decayed: Decimal = decay_base_rate(dec!("0.1"), 12 * 60 * 60);
+        assert!((decayed - dec!("0.05")).checked_abs().unwrap() < dec!("0.000001"));
+        // Partial minutes do not count
+        assert_eq!(decay_base_rate(dec!("0.1"), 59), dec!("0.1"));
+        assert_eq!(decay_base_rate(dec!("0.1"), SECONDS_PER_YEAR), dec!(0));
+    }
+
+    #[test]
+    fn test_redemption_base_rate() {
+        // Redeeming 10% of the debt adds 5%
+        assert_eq!(redemption_base_rate(dec!("0.01"), dec!(100), dec!(1000)), dec!("0.06"));
+        assert_eq!(redemption_base_rate(dec!("0.9"), dec!(1000), dec!(1000)), dec!(1));
+    }
+
+    #[test]
+    fn test_pool_product_rescales() {
+        assert_eq!(pool_product(dec!(1), dec!(250), dec!(1000)), (dec!("0.75"), false));
+        assert_eq!(pool_product(dec!(1), dec!(1000), dec!(1000)), (dec!(0), false));
+        // Keeping a ten millionth of the pool takes P below a billionth
+        assert_eq!(pool_product(dec!("0.001"), dec!("999999.9"), dec!(1_000_000)), (dec!("0.1"), true));
+        assert_eq!(pool_product(dec!("0.001"), dec!(999_999), dec!(1_000_000)), (dec!("0.000000001"), false));
+    }
+
+    #[test]
+    fn test_pool_deposit_and_gain() {
+        // 1000 deposited at P = 1, then a quarter of the pool absorbed for 100 collateral per 1000 deposited
+        assert_eq!(compounded_deposit(dec!(1000), dec!(1), dec!("0.75"), 0), dec!(750));
+        assert_eq!(pool_gain(dec!(1000), dec!(1), dec!(0), dec!("0.1"), dec!(0)), dec!(100));
+
+        // Across a scale change
+        assert_eq!(compounded_deposit(dec!(1000), dec!("0.001"), dec!("0.5"), 1), dec!("0.0005"));
+        assert_eq!(compounded_deposit(dec!(1000), dec!(1), dec!(1), 2), dec!(0));
+        assert_eq!(pool_gain(dec!(1000), dec!("0.5"), dec!("0.1"), dec!("0.2"), dec!(500_000_000)), dec!(1200));
+    }
+
+    #[test]
+    fn test_seize_amount() {
+        // Half of a 500 RSH debt repaid against 1000 tokens at $0.5 with a 10% bonus
+        assert_eq!(seize_amount(dec!(250), dec!(1), dec!(500), dec!(500), dec!("0.1"), dec!("0.5"), dec!(1000)), dec!(550));
+        // Never more than the position holds
+        assert_eq!(seize_amount(dec!(250), dec!(1), dec!(500), dec!(500), dec!("0.1"), dec!("0.1"), dec!(1000)), dec!(1000));
+    }
+}
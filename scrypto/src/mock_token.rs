@@ -0,0 +1,87 @@
+/* ------------------ Imports ----------------- */
+use scrypto::prelude::*;
+
+/* ----------------- Blueprint ---------------- */
+/// Faucet for a named fungible token, standing in for testnet assets during local development
+#[blueprint]
+mod mock_token {
+    /* ------------ Role Authorization ------------ */
+    enable_method_auth! {
+        methods {
+            free_tokens => PUBLIC;
+            token_address => PUBLIC;
+        }
+    }
+
+    /* -------------- Component Data -------------- */
+    struct MockToken {
+        token_manager: ResourceManager,
+        // Rate Limiting
+        allowance: Decimal, // Tokens handed out per window
+        window: i64,        // Window length in seconds
+        window_start: i64,
+        claimed: Decimal,
+    }
+
+    impl MockToken {
+        /* -------------- Public Methods -------------- */
+        pub fn instantiate(name: String, symbol: String, divisibility: u8, allowance: Decimal, window: i64) -> Global<MockToken> {
+            assert!(divisibility <= DIVISIBILITY_MAXIMUM, "Divisibility must be at most {}.", DIVISIBILITY_MAXIMUM);
+            assert!(allowance > Decimal::ZERO, "Allowance must be positive.");
+            assert!(window >= 0, "Window cannot be negative.");
+
+            let (address_reservation, component_address) = Runtime::allocate_component_address(MockToken::blueprint_id());
+
+            let token_manager: ResourceManager = ResourceBuilder::new_fungible(OwnerRole::None)
+                .divisibility(divisibility)
+                .metadata(metadata! {init {
+                    "name"        => name, locked;
+                    "symbol"      => symbol, locked;
+                    "description" => "Mock token for local development, freely available from its faucet", locked;
+                }})
+                .mint_roles(mint_roles! {
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
+
+            Self {
+                token_manager,
+                allowance,
+                window,
+                window_start: Clock::current_time_rounded_to_seconds().seconds_since_unix_epoch,
+                claimed: Decimal::ZERO,
+            }
+            .instantiate()
+            .prepare_to_globalize(OwnerRole::None)
+            .with_address(address_reservation)
+            .globalize()
+        }
+
+        /// Mints tokens, as long as the allowance of the current window has not been used up
+        pub fn free_tokens(&mut self, amount: Decimal) -> Bucket {
+            assert!(amount > Decimal::ZERO, "Amount must be positive.");
+
+            let now: i64 = Clock::current_time_rounded_to_seconds().seconds_since_unix_epoch;
+            if now >= self.window_start + self.window {
+                self.window_start = now;
+                self.claimed = Decimal::ZERO;
+            }
+
+            let claimed: Decimal = self.claimed.checked_add(amount).unwrap();
+            assert!(
+                claimed <= self.allowance,
+                "Faucet allowance exceeded; {:?} of {:?} left in this window",
+                self.allowance.checked_sub(self.claimed).unwrap(),
+                self.allowance
+            );
+            self.claimed = claimed;
+
+            self.token_manager.mint(amount)
+        }
+
+        pub fn token_address(&self) -> ResourceAddress {
+            self.token_manager.address()
+        }
+    }
+}
@@ -1,5 +1,282 @@
 /* ------------------ Imports ----------------- */
 use scrypto::prelude::*;
+use std::collections::HashMap;
 
 /* ------------------- Types ------------------ */
+pub type AddrToAmount = HashMap<ResourceAddress, Decimal>;
 pub type LazySet<T> = KeyValueStore<T, ()>;
+
+/* ----------------- Constants ---------------- */
+pub const SECONDS_PER_YEAR: i64 = 31_536_000;
+/// Positions a single redemption may draw from, bounding its cost
+pub const MAX_REDEMPTION_POSITIONS: usize = 20;
+/// Least healthy indexed positions re-ranked by their live health factor, bounding the cost of a stale index
+pub const RISK_WINDOW: usize = 50;
+
+/* ------------------ Structs ----------------- */
+/// Lifecycle state of a listed asset
+#[derive(ScryptoSbor, ManifestSbor, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetStatus {
+    /// Accepted as collateral / borrowable
+    Active,
+    /// Existing positions are untouched, but no new deposits are accepted
+    Frozen,
+}
+
+/// Per-asset risk parameters, all ratios are expressed as fractions (0.75 = 75%)
+#[derive(ScryptoSbor, ManifestSbor, Debug, Clone, PartialEq, Eq)]
+pub struct RiskConfig {
+    /// Fraction of the collateral value that may be borrowed against
+    pub loan_to_value: Decimal,
+    /// Fraction of the collateral value at which a position becomes liquidatable
+    pub liquidation_threshold: Decimal,
+    /// Extra collateral paid to liquidators on top of the repaid value
+    pub liquidation_bonus: Decimal,
+    /// Maximum amount of the asset that may be held by Radish
+    pub supply_cap: Decimal,
+}
+
+/// Listing information for an asset in the asset list
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq)]
+pub struct AssetConfig {
+    pub id: Decimal,
+    pub status: AssetStatus,
+    pub risk: RiskConfig,
+}
+
+/// Protocol-wide parameters, updatable by admins
+#[derive(ScryptoSbor, ManifestSbor, Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolParameters {
+    /// Annual interest rate charged on outstanding RSH debt
+    pub borrow_rate: Decimal,
+    /// Maximum amount of RSH that may be borrowed across all positions
+    pub debt_ceiling: Decimal,
+    /// Maximum fraction of a position's debt that may be repaid in a single liquidation
+    pub close_factor: Decimal,
+    /// Positions left with less RSH debt than this are closed, settling the rest from their collateral
+    pub dust_threshold: Decimal,
+    /// Minimum health factor a position must keep after collateral is withdrawn without fully repaying
+    pub withdrawal_health_factor: Decimal,
+    /// Lowest fee charged on redemptions, on top of the base rate driven by redemption volume
+    pub redemption_fee_floor: Decimal,
+}
+
+/// Coarse health of a position, shown in wallets
+#[derive(ScryptoSbor, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthTier {
+    /// No debt, nothing to liquidate
+    DebtFree,
+    /// At least twice the collateral needed to avoid liquidation
+    Safe,
+    /// Healthy enough to withdraw collateral
+    Moderate,
+    /// Below the withdrawal health factor, close to liquidation
+    AtRisk,
+    /// Health factor below 1, open to liquidation
+    Liquidatable,
+}
+
+impl HealthTier {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::DebtFree => "Debt free",
+            Self::Safe => "Safe",
+            Self::Moderate => "Moderate",
+            Self::AtRisk => "At risk",
+            Self::Liquidatable => "Liquidatable",
+        }
+    }
+}
+
+/// Actions a delegate may take on a position for its holder; none of them take anything out of the position
+#[derive(ScryptoSbor, ManifestSbor, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DelegatePermissions {
+    /// Deposit more collateral, with `delegate_add_collateral`
+    pub add_collateral: bool,
+    /// Move collateral between positions of the same holder delegating to the same badge, with `delegate_rebalance`
+    pub rebalance: bool,
+}
+
+/// Delegates of a position, granted by the account holding its badge
+///
+/// ! The grants lapse as soon as `holder` no longer holds the badge, e.g. once the position is sold
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct Delegation {
+    pub holder: ComponentAddress,
+    pub delegates: IndexMap<ResourceOrNonFungible, DelegatePermissions>,
+}
+
+impl DelegatePermissions {
+    pub fn is_empty(&self) -> bool {
+        !(self.add_collateral || self.rebalance)
+    }
+}
+
+/// Aggregate state of a single listed asset
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq)]
+pub struct AssetStats {
+    pub asset: ResourceAddress,
+    pub id: Decimal,
+    pub status: AssetStatus,
+    /// USD price, if tracked by the oracle
+    pub price: Option<Decimal>,
+    pub total_supplied: Decimal,
+    pub total_borrowed: Decimal,
+    /// Borrowed over the debt ceiling for RSH, supplied over the supply cap for collateral
+    pub utilization: Decimal,
+    pub borrow_rate: Decimal,
+    pub supply_cap_remaining: Decimal,
+    pub borrow_cap_remaining: Decimal,
+}
+
+/// Aggregate state of the whole protocol
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolStats {
+    pub assets: Vec<AssetStats>,
+    /// USD value of all collateral held, for assets with a tracked price
+    pub total_collateral_value: Decimal,
+    pub total_debt: Decimal,
+    pub debt_ceiling_remaining: Decimal,
+    pub utilization: Decimal,
+    pub borrow_rate: Decimal,
+    pub interest_index: Decimal,
+    pub open_positions: u64,
+    pub paused: bool,
+}
+
+/// Asset list entry, as returned by `list_assets`
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq)]
+pub struct AssetListing {
+    pub asset: ResourceAddress,
+    pub id: Decimal,
+    pub status: AssetStatus,
+    pub risk: RiskConfig,
+    pub price: Option<Decimal>,
+    pub symbol: Option<String>,
+    pub divisibility: u8,
+}
+
+/// A page of the asset list; pass `next` as the `start` of the following query
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq)]
+pub struct AssetPage {
+    pub assets: Vec<AssetListing>,
+    pub next: Option<Decimal>,
+}
+
+/// Health of a single position, as returned by `get_health_many`
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct PositionHealth {
+    pub position: NonFungibleLocalId,
+    pub collateral: AddrToAmount,
+    pub debt: Decimal,
+    pub health_factor: Decimal,
+}
+
+/// A change to a position, as recorded in its history
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq)]
+pub enum PositionAction {
+    /// Opened against collateral, borrowing its full capacity
+    Open { borrowed: Decimal },
+    /// Opened with a share of another position's collateral and debt
+    SplitFrom { position: NonFungibleLocalId },
+    AddCollateral { added: AddrToAmount },
+    Borrow { amount: Decimal },
+    WithdrawCollateral { released: AddrToAmount },
+    Repay { repaid: Decimal, released: AddrToAmount },
+    /// Interest added to the debt since the previous entry, checkpointed with the next change
+    Accrual { interest: Decimal },
+    Liquidation { repaid: Decimal, seized: AddrToAmount },
+    Merge { merged: Vec<NonFungibleLocalId> },
+    MergedInto { position: NonFungibleLocalId },
+    Split { new_position: NonFungibleLocalId, fraction: Decimal },
+    Rebalance { from: NonFungibleLocalId, to: NonFungibleLocalId, asset: ResourceAddress, amount: Decimal },
+    /// Debt redeemed for collateral by an RSH holder, `fees` of it kept by the protocol
+    Redemption { repaid: Decimal, released: AddrToAmount, fees: AddrToAmount },
+    Close,
+}
+
+/// An action on a position, with the collateral and debt it left behind
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub timestamp: i64,
+    pub action: PositionAction,
+    pub collateral: AddrToAmount,
+    pub debt: Decimal,
+}
+
+/// Log of a position's actions, oldest first, indexed from 0
+#[derive(ScryptoSbor)]
+pub struct PositionHistory {
+    pub entries: KeyValueStore<u64, HistoryEntry>,
+    pub length: u64,
+}
+
+/// A page of a position's history; pass `next` as the `cursor` of the following query
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq)]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    pub next: Option<u64>,
+}
+
+/// Outcome of a repayment, shared by `estimate_repay` and `repay_loan`
+#[derive(Debug, Clone)]
+pub struct RepayPlan {
+    /// RSH taken from the repayment
+    pub repaid: Decimal,
+    /// Collateral paid out, rounded down to each resource's divisibility
+    pub released: AddrToAmount,
+    /// Collateral remaining in the position
+    pub collateral: AddrToAmount,
+    /// Fractions of payouts lost to rounding, carried over into the next payout
+    pub remainders: AddrToAmount,
+    /// Debt remaining in the position
+    pub debt: Decimal,
+    /// Collateral kept by the protocol to settle a dust debt
+    pub retained: AddrToAmount,
+    /// Dust debt settled from the retained collateral
+    pub settled: Decimal,
+}
+
+/// A stability pool deposit as of now, as returned by `get_pool_deposit`
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct PoolDeposit {
+    /// RSH left after absorbing liquidations, withdrawable
+    pub deposit: Decimal,
+    /// Collateral seized for the deposit and not yet withdrawn
+    pub gains: AddrToAmount,
+}
+
+/// Outcome of a redemption, shared by `preview_redeem` and `redeem`
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct RedemptionPlan {
+    /// RSH burnt; less than offered once no more positions can be redeemed against
+    pub redeemed: Decimal,
+    /// Fraction of the drawn collateral kept as the fee
+    pub fee_rate: Decimal,
+    /// Base rate after the redemption, decaying until the next one
+    pub base_rate: Decimal,
+    /// Collateral paid out to the redeemer
+    pub released: AddrToAmount,
+    /// Collateral kept by the protocol
+    pub fees: AddrToAmount,
+    /// Resulting state of each position redeemed against, lowest health factor first
+    pub positions: Vec<(NonFungibleLocalId, PositionPreview)>,
+}
+
+/// Resulting state of a position after an action, as returned by the preview methods
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct PositionPreview {
+    pub collateral: AddrToAmount,
+    pub debt: Decimal,
+    pub health_factor: Decimal,
+    /// RSH which may be borrowed against the resulting collateral
+    pub borrow_capacity: Decimal,
+    /// RSH minted to the caller
+    pub borrowed: Decimal,
+    /// RSH taken from the caller
+    pub repaid: Decimal,
+    /// Collateral paid out to the caller
+    pub released: AddrToAmount,
+    /// Collateral charged on top of the debt, i.e. liquidation bonuses and settled dust
+    pub fees: AddrToAmount,
+}
@@ -0,0 +1,44 @@
+/* ------------------ Imports ----------------- */
+// Usages
+use crate::shared::*;
+use scrypto::prelude::*;
+
+/* ------------------ Structs ----------------- */
+#[derive(Debug, NonFungibleData, ScryptoSbor, Clone)]
+pub struct Borrower {
+    #[mutable]
+    pub collateral: AddrToAmount,
+    /// Debt as of the last checkpoint; scale by the current interest index over `debt_index` for the live amount
+    #[mutable]
+    pub debt: Decimal,
+    #[mutable]
+    pub debt_index: Decimal,
+    /// Fractions of collateral payouts lost to rounding, paid out once they add up to a whole unit
+    #[mutable]
+    pub remainders: AddrToAmount,
+    // Wallet display, refreshed whenever the position changes
+    #[mutable]
+    pub description: String,
+    #[mutable]
+    pub key_image_url: Url,
+    /// As of the last change; prices and interest move it in between, see `refresh_position`
+    #[mutable]
+    pub health_tier: HealthTier,
+}
+
+/// A stability pool deposit, valued from a snapshot of the pool taken when it last changed
+#[derive(Debug, NonFungibleData, ScryptoSbor, Clone)]
+pub struct Depositor {
+    /// RSH deposited as of the snapshot, shrinking as the pool absorbs liquidations
+    #[mutable]
+    pub deposit: Decimal,
+    // Pool product, per asset sums, epoch and scale at the snapshot
+    #[mutable]
+    pub product: Decimal,
+    #[mutable]
+    pub sums: AddrToAmount,
+    #[mutable]
+    pub epoch: u64,
+    #[mutable]
+    pub scale: u64,
+}
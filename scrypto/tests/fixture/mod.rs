@@ -0,0 +1,172 @@
+/* ------------------ Imports ----------------- */
+// Usages
+use radish::mock_token::mock_token_test::*;
+use radish::radish_test::*;
+use radish::shared::*;
+use radish_sdk::*;
+use scrypto_test::prelude::*;
+
+/* ----------------- Constants ---------------- */
+pub fn default_risk() -> RiskConfig {
+    RiskConfig {
+        loan_to_value: dec!(0.5),
+        liquidation_threshold: dec!(0.65),
+        liquidation_bonus: dec!(0.1),
+        supply_cap: Decimal::MAX,
+    }
+}
+
+/* -------------- Ledger Simulator ------------ */
+/// A published and instantiated Radish component, with an account holding the owner and an admin badge
+///
+/// Mock tokens are created directly by the ledger, `EnvFixture` claims them from `MockToken` faucets instead
+pub struct LedgerFixture {
+    pub ledger: DefaultLedgerSimulator,
+    pub public_key: Secp256k1PublicKey,
+    pub account: ComponentAddress,
+    pub radish: RadishManifests,
+    round: u64,
+}
+
+impl LedgerFixture {
+    pub fn new() -> Self {
+        let mut ledger: DefaultLedgerSimulator = LedgerSimulatorBuilder::new().build();
+        let (public_key, _private_key, account) = ledger.new_allocated_account();
+        let package: PackageAddress = ledger.compile_and_publish(this_package!());
+
+        let receipt: TransactionReceipt = ledger.execute_manifest(
+            RadishManifests::instantiate(package, account),
+            vec![NonFungibleGlobalId::from_public_key(&public_key)],
+        );
+        let commit: &CommitResult = receipt.expect_commit_success();
+        let component: ComponentAddress = commit.new_component_addresses()[0];
+
+        // Tell the new resources apart by their names
+        let find = |name: &str| -> ResourceAddress {
+            *commit
+                .new_resource_addresses()
+                .iter()
+                .find(|resource| ledger.get_metadata((**resource).into(), "name") == Some(MetadataValue::String(name.to_string())))
+                .unwrap_or_else(|| panic!("No resource named {}", name))
+        };
+        let owner_badge: ResourceAddress = find("Radish Owner Badge");
+        let radish: RadishManifests = RadishManifests::new(
+            component,
+            find("Radish"),
+            find("Radish Borrower Badge"),
+            find("Radish Stability Pool Depositor Badge"),
+            find("Radish Admin Badge"),
+        );
+
+        let mut fixture: Self = Self {
+            ledger,
+            public_key,
+            account,
+            radish,
+            round: 0,
+        };
+        let manifest: TransactionManifestV1 = fixture.radish.mint_admin_badges(account, owner_badge, dec!(1));
+        fixture.execute(manifest).expect_commit_success();
+
+        fixture
+    }
+
+    /// Executes the manifest signed by the fixture account
+    pub fn execute(&mut self, manifest: TransactionManifestV1) -> TransactionReceipt {
+        self.ledger
+            .execute_manifest(manifest, vec![NonFungibleGlobalId::from_public_key(&self.public_key)])
+    }
+
+    /// Creates a mock token held by the fixture account, then lists and prices it
+    pub fn create_token(&mut self, divisibility: u8, price: Decimal) -> ResourceAddress {
+        let token: ResourceAddress = self.ledger.create_fungible_resource(dec!(1000000), divisibility, self.account);
+
+        let manifest: TransactionManifestV1 = self.radish.add_asset(self.account, token, default_risk());
+        self.execute(manifest).expect_commit_success();
+        self.set_price(token, price);
+
+        token
+    }
+
+    pub fn set_price(&mut self, asset: ResourceAddress, price: Decimal) {
+        let manifest: TransactionManifestV1 = self.radish.set_price(self.account, asset, price);
+        self.execute(manifest).expect_commit_success();
+    }
+
+    /// Opens a position against the collateral, returning its id
+    pub fn open_loan(&mut self, collateral: AddrToAmount) -> NonFungibleLocalId {
+        let manifest: TransactionManifestV1 = self.radish.get_loan(self.account, &collateral);
+        let receipt: TransactionReceipt = self.execute(manifest);
+
+        self.events(&receipt)
+            .into_iter()
+            .find_map(|event| match event {
+                RadishEvent::Borrow(event) => Some(event.position),
+                _ => None,
+            })
+            .expect("No borrow event emitted")
+    }
+
+    /// Decodes all Radish events of a committed transaction
+    pub fn events(&self, receipt: &TransactionReceipt) -> Vec<RadishEvent> {
+        receipt
+            .expect_commit_success()
+            .application_events
+            .iter()
+            .filter_map(|(identifier, data)| RadishEvent::decode(&self.ledger.event_name(identifier), data).unwrap())
+            .collect()
+    }
+
+    /// Executes a manifest expected to fail, returning the Radish error it failed with
+    pub fn expect_error(&mut self, manifest: TransactionManifestV1) -> RadishError {
+        let receipt: TransactionReceipt = self.execute(manifest);
+        let failure: String = format!("{:?}", receipt.expect_commit_failure().outcome);
+        decode_error(&failure).unwrap_or_else(|| panic!("Not a Radish error: {}", failure))
+    }
+
+    /// Moves the ledger clock forward, starting a new consensus round
+    pub fn advance_time(&mut self, seconds: i64) {
+        let timestamp_ms: i64 = self.ledger.get_current_proposer_timestamp_ms() + seconds * 1000;
+        self.round += 1;
+        self.ledger.advance_to_round_at_timestamp(Round::of(self.round), timestamp_ms);
+    }
+
+    pub fn balance(&mut self, resource: ResourceAddress) -> Decimal {
+        self.ledger.get_component_balance(self.account, resource)
+    }
+}
+
+/* ------------- Test Environment ------------- */
+/// A Radish component in a `TestEnvironment`, with the auth module disabled so admin methods can be called directly
+pub struct EnvFixture {
+    pub env: TestEnvironment<InMemorySubstateDatabase>,
+    pub package: PackageAddress,
+    pub radish: Radish,
+}
+
+impl EnvFixture {
+    pub fn new() -> Result<Self, RuntimeError> {
+        let mut env: TestEnvironment<InMemorySubstateDatabase> = TestEnvironment::new();
+        let package: PackageAddress = PackageFactory::compile_and_publish(this_package!(), &mut env, CompileProfile::Fast)?;
+        let (radish, _owner_badge) = Radish::instantiate(package, &mut env)?;
+        env.disable_auth_module();
+
+        Ok(Self {
+            env,
+            package,
+            radish,
+        })
+    }
+
+    /// Claims a mock token from a new faucet, then lists and prices it
+    pub fn create_token(&mut self, amount: Decimal, divisibility: u8, price: Decimal) -> Result<Bucket, RuntimeError> {
+        let mut faucet: MockToken = MockToken::instantiate("Mock".to_string(), "MOCK".to_string(), divisibility, amount, 0, self.package, &mut self.env)?;
+        let bucket: Bucket = faucet.free_tokens(amount, &mut self.env)?;
+        let token: ResourceAddress = bucket.resource_address(&mut self.env)?;
+
+        self.radish.add_asset(token, default_risk(), &mut self.env)?;
+        self.radish.set_price(token, price, &mut self.env)?;
+
+        Ok(bucket)
+    }
+}
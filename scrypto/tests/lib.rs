@@ -1,68 +1,953 @@
-// use scrypto_test::prelude::*;
-
-// use radish_backend::radish_test::*;
-
-// #[test]
-// fn test_radish() {
-//     // Setup the environment
-//     let mut ledger = LedgerSimulatorBuilder::new().build();
-
-//     // Create an account
-//     let (public_key, _private_key, account) = ledger.new_allocated_account();
-
-//     // Publish package
-//     let package_address = ledger.compile_and_publish(this_package!());
-
-//     // Test the `instantiate_hello` function.
-//     let manifest = ManifestBuilder::new()
-//         .lock_fee_from_faucet()
-//         .call_function(
-//             package_address,
-//             "Hello",
-//             "instantiate_hello",
-//             manifest_args!(),
-//         )
-//         .build();
-//     let receipt = ledger.execute_manifest(
-//         manifest,
-//         vec![NonFungibleGlobalId::from_public_key(&public_key)],
-//     );
-//     println!("{:?}\n", receipt);
-//     let component = receipt.expect_commit(true).new_component_addresses()[0];
-
-//     // Test the `free_token` method.
-//     let manifest = ManifestBuilder::new()
-//         .lock_fee_from_faucet()
-//         .call_method(component, "free_token", manifest_args!())
-//         .call_method(
-//             account,
-//             "deposit_batch",
-//             manifest_args!(ManifestExpression::EntireWorktop),
-//         )
-//         .build();
-//     let receipt = ledger.execute_manifest(
-//         manifest,
-//         vec![NonFungibleGlobalId::from_public_key(&public_key)],
-//     );
-//     println!("{:?}\n", receipt);
-//     receipt.expect_commit_success();
-// }
-
-// #[test]
-// fn test_hello_with_test_environment() -> Result<(), RuntimeError> {
-//     // Arrange
-//     let mut env = TestEnvironment::new();
-//     let package_address = 
-//         PackageFactory::compile_and_publish(this_package!(), &mut env, CompileProfile::Fast)?;
-
-//     let mut hello = Hello::instantiate_hello(package_address, &mut env)?;
-
-//     // Act
-//     let bucket = hello.free_token(&mut env)?;
-
-//     // Assert
-//     let amount = bucket.amount(&mut env)?;
-//     assert_eq!(amount, dec!("1"));
-
-//     Ok(())
-// }
+/* ------------------ Imports ----------------- */
+// Modules
+mod fixture;
+// Usages
+use fixture::*;
+use radish::display::tier_colour;
+use radish::events::*;
+use radish::mock_token::mock_token_test::*;
+use radish::radish_test::*;
+use radish::shared::*;
+use radish::user::Borrower;
+use radish_sdk::*;
+use scrypto_test::prelude::*;
+
+/* -------------- Ledger Simulator ------------ */
+#[test]
+fn test_add_and_remove_asset() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+
+    let manifest: TransactionManifestV1 = fixture.radish.list_assets(fixture.account, dec!(0), 10);
+    let page: AssetPage = fixture.execute(manifest).expect_commit_success().output(1);
+    assert!(page.assets.iter().any(|listing| listing.asset == token && listing.divisibility == 18));
+
+    let manifest: TransactionManifestV1 = fixture.radish.list_assets(fixture.account, dec!(0), 0);
+    let empty: AssetPage = fixture.execute(manifest).expect_commit_success().output(1);
+    assert!(empty.assets.is_empty());
+    assert_eq!(empty.next, page.assets.first().map(|listing| listing.id));
+
+    let manifest: TransactionManifestV1 = fixture.radish.remove_asset(fixture.account, token);
+    let receipt: TransactionReceipt = fixture.execute(manifest);
+    assert!(fixture
+        .events(&receipt)
+        .iter()
+        .any(|event| matches!(event, RadishEvent::AssetRemoved(event) if event.asset == token)));
+
+    let manifest: TransactionManifestV1 = fixture.radish.list_assets(fixture.account, dec!(0), 10);
+    let page: AssetPage = fixture.execute(manifest).expect_commit_success().output(1);
+    assert!(page.assets.iter().all(|listing| listing.asset != token));
+}
+
+#[test]
+fn test_cannot_remove_asset_holding_collateral() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    fixture.open_loan(AddrToAmount::from([(token, dec!(100))]));
+
+    let manifest: TransactionManifestV1 = fixture.radish.remove_asset(fixture.account, token);
+    let error: RadishError = fixture.expect_error(manifest);
+    assert_eq!(error.code(), 104);
+    assert!(matches!(error, RadishError::AssetVaultNotEmpty { asset, amount } if asset == token && amount == dec!(100)));
+}
+
+#[test]
+fn test_borrow_against_collateral() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(2));
+    let rsh: ResourceAddress = fixture.radish.radish;
+    let rsh_before: Decimal = fixture.balance(rsh);
+
+    fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+
+    // 1000 tokens at $2 with a 50% loan to value, RSH priced at $1
+    assert_eq!(fixture.balance(rsh) - rsh_before, dec!(1000));
+    assert_eq!(fixture.balance(fixture.radish.borrower_badge), dec!(1));
+}
+
+#[test]
+fn test_repay_loan() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let token_before: Decimal = fixture.balance(token);
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+
+    // Partial repayment releases collateral pro rata
+    let manifest: TransactionManifestV1 = fixture.radish.repay_loan(fixture.account, position.clone(), dec!(100));
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(token), token_before - dec!(800));
+
+    // Repaying the remaining debt empties the position, which can then be closed
+    let manifest: TransactionManifestV1 = fixture.radish.close_position(fixture.account, position.clone());
+    assert!(matches!(fixture.expect_error(manifest), RadishError::PositionNotEmpty { .. }));
+
+    let manifest: TransactionManifestV1 = fixture.radish.repay_loan(fixture.account, position.clone(), dec!(400));
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(token), token_before);
+
+    let manifest: TransactionManifestV1 = fixture.radish.close_position(fixture.account, position);
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(fixture.radish.borrower_badge), dec!(0));
+}
+
+#[test]
+fn test_repay_rounds_payouts_and_closes_dust() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(0, dec!(1));
+    let token_before: Decimal = fixture.balance(token);
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(10))]));
+
+    // 10 * 1.2 / 5 = 2.4 tokens are released, paid out as whole tokens with the fraction carried over
+    let manifest: TransactionManifestV1 = fixture.radish.repay_loan(fixture.account, position.clone(), dec!(1.2));
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(token), token_before - dec!(8));
+
+    // Leaving 0.005 RSH of debt closes the position, retaining a whole token to settle it
+    let manifest: TransactionManifestV1 = fixture.radish.repay_loan(fixture.account, position.clone(), dec!(3.795));
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(token), token_before - dec!(1));
+
+    let manifest: TransactionManifestV1 = fixture.radish.close_position(fixture.account, position);
+    fixture.execute(manifest).expect_commit_success();
+}
+
+#[test]
+fn test_repeated_repays_carry_remainders_once() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(0, dec!(1));
+    let token_before: Decimal = fixture.balance(token);
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(10))]));
+
+    // 10 * 0.45 / 5 = 0.9 tokens are released, all of it carried over
+    let manifest: TransactionManifestV1 = fixture.radish.repay_loan(fixture.account, position.clone(), dec!(0.45));
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(token), token_before - dec!(10));
+
+    // (10 - 0.9) * 4.5 / 4.55 + 0.9 = 9.9 tokens, the carried fraction is only counted once
+    let manifest: TransactionManifestV1 = fixture.radish.repay_loan(fixture.account, position.clone(), dec!(4.5));
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(token), token_before - dec!(1));
+
+    let manifest: TransactionManifestV1 = fixture.radish.repay_loan(fixture.account, position, dec!(0.05));
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(token), token_before);
+}
+
+#[test]
+fn test_remainders_do_not_outlive_collateral_changes() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(0, dec!(1));
+    let token_before: Decimal = fixture.balance(token);
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(10))]));
+
+    // 0.9 tokens carried over, then dropped by the withdrawal
+    let manifest: TransactionManifestV1 = fixture.radish.repay_loan(fixture.account, position.clone(), dec!(0.45));
+    fixture.execute(manifest).expect_commit_success();
+    let manifest: TransactionManifestV1 = fixture.radish.withdraw_collateral(fixture.account, position.clone(), token, dec!(1));
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(token), token_before - dec!(9));
+
+    // 9 * 0.91 / 4.55 = 1.8 tokens, a stale carry would have made it (9 - 0.9) * 0.2 + 0.9 = 2.52
+    let manifest: TransactionManifestV1 = fixture.radish.repay_loan(fixture.account, position, dec!(0.91));
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(token), token_before - dec!(8));
+}
+
+#[test]
+fn test_add_collateral_rescues_position() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+    fixture.set_price(token, dec!(0.5));
+
+    // Health factor: 2000 * 0.5 * 0.65 / 500 = 1.3
+    let manifest: TransactionManifestV1 = fixture
+        .radish
+        .add_collateral(fixture.account, position.clone(), &AddrToAmount::from([(token, dec!(1000))]));
+    let receipt: TransactionReceipt = fixture.execute(manifest);
+    assert!(fixture
+        .events(&receipt)
+        .iter()
+        .any(|event| matches!(event, RadishEvent::Supply(event) if event.collateral.get(&token) == Some(&dec!(2000)))));
+
+    let manifest: TransactionManifestV1 = fixture.radish.preview_liquidate(fixture.account, position, dec!(100));
+    assert!(matches!(
+        fixture.expect_error(manifest),
+        RadishError::PositionHealthy { health_factor } if health_factor == dec!(1.3)
+    ));
+}
+
+#[test]
+fn test_borrow_more_up_to_capacity() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+
+    // A doubled price doubles the capacity to 1000 RSH
+    fixture.set_price(token, dec!(2));
+    let radish_before: Decimal = fixture.balance(fixture.radish.radish);
+    let manifest: TransactionManifestV1 = fixture.radish.borrow_more(fixture.account, position.clone(), dec!(500));
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(fixture.radish.radish), radish_before + dec!(500));
+
+    let manifest: TransactionManifestV1 = fixture.radish.borrow_more(fixture.account, position, dec!(1));
+    assert!(matches!(
+        fixture.expect_error(manifest),
+        RadishError::InsufficientCollateral { debt, borrow_capacity } if debt == dec!(1001) && borrow_capacity == dec!(1000)
+    ));
+}
+
+#[test]
+fn test_withdraw_and_repay_specific_collateral() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let kept: ResourceAddress = fixture.create_token(18, dec!(1));
+    let withdrawn: ResourceAddress = fixture.create_token(18, dec!(1));
+    let withdrawn_before: Decimal = fixture.balance(withdrawn);
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(kept, dec!(1000)), (withdrawn, dec!(1000))]));
+
+    // Health factor: 1950 * 0.65 / 1000 = 1.2675, above the 1.25 margin
+    let manifest: TransactionManifestV1 = fixture.radish.withdraw_collateral(fixture.account, position.clone(), withdrawn, dec!(50));
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(withdrawn), withdrawn_before - dec!(950));
+
+    let manifest: TransactionManifestV1 = fixture.radish.withdraw_collateral(fixture.account, position.clone(), withdrawn, dec!(50));
+    assert!(matches!(fixture.expect_error(manifest), RadishError::HealthBelowMargin { .. }));
+
+    // Repaying 40% of the debt releases 40% of the collateral value (780), all of it from the chosen asset
+    let manifest: TransactionManifestV1 = fixture.radish.repay_targeted(fixture.account, position, dec!(400), withdrawn);
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(withdrawn), withdrawn_before - dec!(170));
+}
+
+#[test]
+fn test_withdraw_collateral_while_paused() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let collateral: AddrToAmount = AddrToAmount::from([(token, dec!(1000))]);
+    let position: NonFungibleLocalId = fixture.open_loan(collateral.clone());
+    let manifest: TransactionManifestV1 = fixture.radish.add_collateral(fixture.account, position.clone(), &collateral);
+    fixture.execute(manifest).expect_commit_success();
+
+    let manifest: TransactionManifestV1 = fixture.radish.set_paused(fixture.account, true);
+    fixture.execute(manifest).expect_commit_success();
+    let manifest: TransactionManifestV1 = fixture.radish.withdraw_collateral(fixture.account, position.clone(), token, dec!(100));
+    assert!(matches!(fixture.expect_error(manifest), RadishError::Paused));
+
+    let manifest: TransactionManifestV1 = fixture.radish.set_paused(fixture.account, false);
+    fixture.execute(manifest).expect_commit_success();
+    let manifest: TransactionManifestV1 = fixture.radish.withdraw_collateral(fixture.account, position, token, dec!(100));
+    fixture.execute(manifest).expect_commit_success();
+}
+
+#[test]
+fn test_repay_targeted_rejects_repaid_position() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+
+    let manifest: TransactionManifestV1 = fixture.radish.repay_for(fixture.account, position.clone(), dec!(500));
+    fixture.execute(manifest).expect_commit_success();
+
+    let manifest: TransactionManifestV1 = fixture.radish.repay_targeted(fixture.account, position, dec!(100), token);
+    assert!(matches!(
+        fixture.expect_error(manifest),
+        RadishError::InvalidRepayment { amount, .. } if amount == dec!(100)
+    ));
+}
+
+#[test]
+fn test_repay_for_keeps_collateral_in_position() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+    fixture.set_price(token, dec!(0.5));
+    let token_before: Decimal = fixture.balance(token);
+    let radish_before: Decimal = fixture.balance(fixture.radish.radish);
+
+    // Health factor: 1000 * 0.5 * 0.65 / 200 = 1.625
+    let manifest: TransactionManifestV1 = fixture.radish.repay_for(fixture.account, position.clone(), dec!(300));
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(fixture.balance(token), token_before);
+    assert_eq!(fixture.balance(fixture.radish.radish), radish_before - dec!(300));
+
+    let manifest: TransactionManifestV1 = fixture.radish.preview_liquidate(fixture.account, position, dec!(100));
+    assert!(matches!(
+        fixture.expect_error(manifest),
+        RadishError::PositionHealthy { health_factor } if health_factor == dec!(1.625)
+    ));
+}
+
+#[test]
+fn test_repay_many_worst_health_first() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let healthy_token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let risky_token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let healthy: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(healthy_token, dec!(1000))]));
+    let risky: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(risky_token, dec!(1000))]));
+    fixture.set_price(risky_token, dec!(0.9));
+
+    let manifest: TransactionManifestV1 = fixture.radish.get_health_many(fixture.account, vec![healthy.clone(), risky.clone()]);
+    let healths: Vec<PositionHealth> = fixture.execute(manifest).expect_commit_success().output(1);
+    assert_eq!(healths[0].position, risky);
+    assert_eq!(healths[1].position, healthy);
+
+    // Repayments are applied worst health first, whatever the request order
+    let manifest: TransactionManifestV1 = fixture
+        .radish
+        .repay_many(fixture.account, &[(healthy.clone(), dec!(100)), (risky.clone(), dec!(50))]);
+    let receipt: TransactionReceipt = fixture.execute(manifest);
+    let repaid: Vec<(NonFungibleLocalId, Decimal)> = fixture
+        .events(&receipt)
+        .into_iter()
+        .filter_map(|event| match event {
+            RadishEvent::Repay(event) => Some((event.position, event.amount)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(repaid, vec![(risky, dec!(50)), (healthy, dec!(100))]);
+}
+
+#[test]
+fn test_merge_and_split_positions() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let first: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+    let second: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(600))]));
+
+    let manifest: TransactionManifestV1 = fixture.radish.merge_positions(fixture.account, vec![first.clone(), second.clone()]);
+    let receipt: TransactionReceipt = fixture.execute(manifest);
+    let merge: MergeEvent = fixture
+        .events(&receipt)
+        .into_iter()
+        .find_map(|event| match event {
+            RadishEvent::Merge(event) => Some(event),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(merge.collateral.get(&token), Some(&dec!(1600)));
+    assert_eq!(merge.debt, dec!(800));
+
+    // The emptied position can be closed
+    let manifest: TransactionManifestV1 = fixture.radish.close_position(fixture.account, second);
+    fixture.execute(manifest).expect_commit_success();
+
+    let manifest: TransactionManifestV1 = fixture.radish.split_position(fixture.account, first, dec!(0.25));
+    let receipt: TransactionReceipt = fixture.execute(manifest);
+    let split: SplitEvent = fixture
+        .events(&receipt)
+        .into_iter()
+        .find_map(|event| match event {
+            RadishEvent::Split(event) => Some(event),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(split.collateral.get(&token), Some(&dec!(1200)));
+    assert_eq!(split.debt, dec!(600));
+    assert_eq!(split.new_collateral.get(&token), Some(&dec!(400)));
+    assert_eq!(split.new_debt, dec!(200));
+    assert_eq!(fixture.balance(fixture.radish.borrower_badge), dec!(2));
+}
+
+#[test]
+fn test_split_shares_carried_remainders() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(0, dec!(1));
+    let token_before: Decimal = fixture.balance(token);
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(10))]));
+
+    // 0.9 tokens carried over, split into 5 tokens and 2.275 RSH of debt per half, each carrying 0.45
+    let manifest: TransactionManifestV1 = fixture.radish.repay_loan(fixture.account, position.clone(), dec!(0.45));
+    fixture.execute(manifest).expect_commit_success();
+    let manifest: TransactionManifestV1 = fixture.radish.split_position(fixture.account, position.clone(), dec!(0.5));
+    let receipt: TransactionReceipt = fixture.execute(manifest);
+    let split: NonFungibleLocalId = fixture
+        .events(&receipt)
+        .into_iter()
+        .find_map(|event| match event {
+            RadishEvent::Split(event) => Some(event.new_position),
+            _ => None,
+        })
+        .unwrap();
+
+    // (5 - 0.45) * 0.1 + 0.45 = 0.905 tokens for either half, the kept one carrying all 0.9 would have paid out 1
+    for half in [position.clone(), split.clone()] {
+        let manifest: TransactionManifestV1 = fixture.radish.repay_loan(fixture.account, half, dec!(0.2275));
+        fixture.execute(manifest).expect_commit_success();
+        assert_eq!(fixture.balance(token), token_before - dec!(10));
+    }
+
+    for half in [position, split] {
+        let manifest: TransactionManifestV1 = fixture.radish.repay_loan(fixture.account, half, dec!(2.0475));
+        fixture.execute(manifest).expect_commit_success();
+    }
+    assert_eq!(fixture.balance(token), token_before);
+}
+
+#[test]
+fn test_delegate_manages_position_without_withdrawing() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let first: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+    let second: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+
+    // A bot account, delegated to through its signature badge
+    let (bot_key, _bot_private_key, bot) = fixture.ledger.new_allocated_account();
+    let bot_badge: NonFungibleGlobalId = NonFungibleGlobalId::from_public_key(&bot_key);
+    let delegate: ResourceOrNonFungible = ResourceOrNonFungible::NonFungible(bot_badge.clone());
+    let manifest: TransactionManifestV1 = ManifestBuilder::new()
+        .lock_fee(fixture.account, DEFAULT_FEE)
+        .withdraw_from_account(fixture.account, token, dec!(200))
+        .try_deposit_entire_worktop_or_abort(bot, None)
+        .build();
+    fixture.execute(manifest).expect_commit_success();
+
+    let all: DelegatePermissions = DelegatePermissions {
+        add_collateral: true,
+        rebalance: true,
+    };
+    let rebalance_only: DelegatePermissions = DelegatePermissions {
+        rebalance: true,
+        ..Default::default()
+    };
+    let manifest: TransactionManifestV1 = fixture.radish.set_delegate(fixture.account, first.clone(), delegate.clone(), all);
+    fixture.execute(manifest).expect_commit_success();
+    let manifest: TransactionManifestV1 = fixture.radish.set_delegate(fixture.account, second.clone(), delegate.clone(), rebalance_only);
+    fixture.execute(manifest).expect_commit_success();
+
+    let added: AddrToAmount = AddrToAmount::from([(token, dec!(100))]);
+    let manifest: TransactionManifestV1 = fixture.radish.delegate_add_collateral(bot, delegate.clone(), first.clone(), &added);
+    fixture.ledger.execute_manifest(manifest, vec![bot_badge.clone()]).expect_commit_success();
+
+    // Permissions are scoped per position
+    let manifest: TransactionManifestV1 = fixture.radish.delegate_add_collateral(bot, delegate.clone(), second.clone(), &added);
+    let receipt: TransactionReceipt = fixture.ledger.execute_manifest(manifest, vec![bot_badge.clone()]);
+    let failure: String = format!("{:?}", receipt.expect_commit_failure().outcome);
+    assert!(matches!(decode_error(&failure), Some(RadishError::DelegateNotAuthorized { .. })));
+
+    // Naming a delegate is not enough, its badge must be in the auth zone
+    let manifest: TransactionManifestV1 =
+        fixture.radish.delegate_rebalance(fixture.account, delegate.clone(), first.clone(), second.clone(), token, dec!(100));
+    fixture.execute(manifest).expect_commit_failure();
+
+    // Health factor of the source: 1000 * 0.65 / 500 = 1.3
+    let manifest: TransactionManifestV1 = fixture
+        .radish
+        .delegate_rebalance(bot, delegate.clone(), first.clone(), second.clone(), token, dec!(100));
+    let receipt: TransactionReceipt = fixture.ledger.execute_manifest(manifest, vec![bot_badge.clone()]);
+    let rebalance: RebalanceEvent = fixture
+        .events(&receipt)
+        .into_iter()
+        .find_map(|event| match event {
+            RadishEvent::Rebalance(event) => Some(event),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(rebalance.from_collateral.get(&token), Some(&dec!(1000)));
+    assert_eq!(rebalance.to_collateral.get(&token), Some(&dec!(1100)));
+
+    // Revoking leaves no delegates behind
+    let revoked: DelegatePermissions = DelegatePermissions::default();
+    let manifest: TransactionManifestV1 = fixture.radish.set_delegate(fixture.account, first.clone(), delegate.clone(), revoked);
+    fixture.execute(manifest).expect_commit_success();
+    let manifest: TransactionManifestV1 = fixture.radish.get_delegates(fixture.account, first);
+    let delegates: Vec<(ResourceOrNonFungible, DelegatePermissions)> = fixture.execute(manifest).expect_commit_success().output(1);
+    assert!(delegates.is_empty());
+}
+
+#[test]
+fn test_delegations_stay_with_their_holder() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let victim: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+    let other: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+
+    // Another account holding the other position, both delegating rebalancing to the same bot
+    let (holder_key, _holder_private_key, holder) = fixture.ledger.new_allocated_account();
+    let holder_badge: NonFungibleGlobalId = NonFungibleGlobalId::from_public_key(&holder_key);
+    let transfer = |fixture: &mut LedgerFixture, position: &NonFungibleLocalId| {
+        let manifest: TransactionManifestV1 = ManifestBuilder::new()
+            .lock_fee(fixture.account, DEFAULT_FEE)
+            .withdraw_non_fungibles_from_account(fixture.account, fixture.radish.borrower_badge, [position.clone()])
+            .try_deposit_entire_worktop_or_abort(holder, None)
+            .build();
+        fixture.execute(manifest).expect_commit_success();
+    };
+    transfer(&mut fixture, &other);
+
+    let (bot_key, _bot_private_key, bot) = fixture.ledger.new_allocated_account();
+    let bot_badge: NonFungibleGlobalId = NonFungibleGlobalId::from_public_key(&bot_key);
+    let delegate: ResourceOrNonFungible = ResourceOrNonFungible::NonFungible(bot_badge.clone());
+    let all: DelegatePermissions = DelegatePermissions {
+        add_collateral: true,
+        rebalance: true,
+    };
+    let manifest: TransactionManifestV1 = fixture.radish.set_delegate(fixture.account, victim.clone(), delegate.clone(), all);
+    fixture.execute(manifest).expect_commit_success();
+    let manifest: TransactionManifestV1 = fixture.radish.set_delegate(holder, other.clone(), delegate.clone(), all);
+    fixture.ledger.execute_manifest(manifest, vec![holder_badge]).expect_commit_success();
+
+    // Collateral cannot be moved into a position of another holder
+    let manifest: TransactionManifestV1 = fixture
+        .radish
+        .delegate_rebalance(bot, delegate.clone(), victim.clone(), other.clone(), token, dec!(100));
+    let receipt: TransactionReceipt = fixture.ledger.execute_manifest(manifest, vec![bot_badge.clone()]);
+    let failure: String = format!("{:?}", receipt.expect_commit_failure().outcome);
+    assert!(matches!(decode_error(&failure), Some(RadishError::PositionNotHeld { position, .. }) if position == other));
+
+    // Once transferred, the delegations of the previous holder lapse
+    transfer(&mut fixture, &victim);
+    let manifest: TransactionManifestV1 = fixture.radish.get_delegates(fixture.account, victim.clone());
+    let delegates: Vec<(ResourceOrNonFungible, DelegatePermissions)> = fixture.execute(manifest).expect_commit_success().output(1);
+    assert!(delegates.is_empty());
+
+    let manifest: TransactionManifestV1 = fixture
+        .radish
+        .delegate_rebalance(bot, delegate, victim, other, token, dec!(100));
+    let receipt: TransactionReceipt = fixture.ledger.execute_manifest(manifest, vec![bot_badge]);
+    let failure: String = format!("{:?}", receipt.expect_commit_failure().outcome);
+    assert!(matches!(decode_error(&failure), Some(RadishError::DelegateNotAuthorized { .. })));
+}
+
+#[test]
+fn test_set_delegate_requires_holder_to_hold_position() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+    let (_stranger_key, _stranger_private_key, stranger) = fixture.ledger.new_allocated_account();
+
+    // Proven by the holding account, but naming an account without the badge as its holder
+    let delegate: ResourceOrNonFungible = ResourceOrNonFungible::Resource(token);
+    let permissions: DelegatePermissions = DelegatePermissions {
+        add_collateral: true,
+        rebalance: true,
+    };
+    let manifest: TransactionManifestV1 = ManifestBuilder::new()
+        .lock_fee(fixture.account, DEFAULT_FEE)
+        .create_proof_from_account_of_non_fungibles(fixture.account, fixture.radish.borrower_badge, [position.clone()])
+        .pop_from_auth_zone("position")
+        .radish_set_delegate(fixture.radish.component, "position", stranger, delegate, permissions)
+        .build();
+    let error: RadishError = fixture.expect_error(manifest);
+    assert_eq!(error.code(), 412);
+    assert!(matches!(error, RadishError::PositionNotHeld { position: held, holder } if held == position && holder == stranger));
+
+    let manifest: TransactionManifestV1 = fixture.radish.get_delegates(fixture.account, position);
+    let delegates: Vec<(ResourceOrNonFungible, DelegatePermissions)> = fixture.execute(manifest).expect_commit_success().output(1);
+    assert!(delegates.is_empty());
+}
+
+#[test]
+fn test_borrower_badge_displays_position() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+
+    // Health factor: 1000 * 0.65 / 500 = 1.3
+    let borrower: Borrower = fixture.ledger.get_non_fungible_data(fixture.radish.borrower_badge, position.clone());
+    assert_eq!(borrower.health_tier, HealthTier::Moderate);
+    assert!(borrower.description.contains("owing 500 RSH. Health factor 1.3 (Moderate)"));
+    assert!(borrower.key_image_url.as_str().starts_with("data:image/svg+xml,"));
+
+    // Price changes only show up once the position is refreshed, 1000 * 0.9 * 0.65 / 500 = 1.17
+    fixture.set_price(token, dec!(0.9));
+    let manifest: TransactionManifestV1 = fixture.radish.refresh_position(fixture.account, position.clone());
+    fixture.execute(manifest).expect_commit_success();
+    let borrower: Borrower = fixture.ledger.get_non_fungible_data(fixture.radish.borrower_badge, position.clone());
+    assert_eq!(borrower.health_tier, HealthTier::AtRisk);
+    assert!(borrower.key_image_url.as_str().contains(&tier_colour(HealthTier::AtRisk).replace('#', "%23")));
+
+    // Every change to the position updates its display
+    let manifest: TransactionManifestV1 = fixture.radish.repay_for(fixture.account, position.clone(), dec!(500));
+    fixture.execute(manifest).expect_commit_success();
+    let borrower: Borrower = fixture.ledger.get_non_fungible_data(fixture.radish.borrower_badge, position);
+    assert_eq!(borrower.health_tier, HealthTier::DebtFree);
+    assert!(borrower.description.contains("owing 0 RSH. Debt free"));
+}
+
+#[test]
+fn test_position_history_is_paginated() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+
+    let added: AddrToAmount = AddrToAmount::from([(token, dec!(1000))]);
+    let manifest: TransactionManifestV1 = fixture.radish.add_collateral(fixture.account, position.clone(), &added);
+    fixture.execute(manifest).expect_commit_success();
+
+    // A year of 5% interest is checkpointed by the next change, 500 * 0.05 = 25
+    fixture.advance_time(SECONDS_PER_YEAR);
+    let manifest: TransactionManifestV1 = fixture.radish.borrow_more(fixture.account, position.clone(), dec!(100));
+    fixture.execute(manifest).expect_commit_success();
+
+    let manifest: TransactionManifestV1 = fixture.radish.get_position_history(fixture.account, position.clone(), 0, 3);
+    let page: HistoryPage = fixture.execute(manifest).expect_commit_success().output(1);
+    let actions: Vec<PositionAction> = page.entries.iter().map(|entry| entry.action.clone()).collect();
+    assert_eq!(
+        actions,
+        vec![
+            PositionAction::Open { borrowed: dec!(500) },
+            PositionAction::AddCollateral { added },
+            PositionAction::Accrual { interest: dec!(25) },
+        ]
+    );
+    assert_eq!(page.entries[2].debt, dec!(525));
+    assert_eq!(page.next, Some(3));
+
+    let manifest: TransactionManifestV1 = fixture.radish.get_position_history(fixture.account, position, 3, 3);
+    let page: HistoryPage = fixture.execute(manifest).expect_commit_success().output(1);
+    assert_eq!(page.entries.len(), 1);
+    assert_eq!(page.entries[0].action, PositionAction::Borrow { amount: dec!(100) });
+    assert_eq!(page.entries[0].debt, dec!(625));
+    assert_eq!(page.next, None);
+}
+
+#[test]
+fn test_riskiest_positions_follow_position_changes() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let collateral: AddrToAmount = AddrToAmount::from([(token, dec!(1000))]);
+    let risky: NonFungibleLocalId = fixture.open_loan(collateral.clone());
+    let safe: NonFungibleLocalId = fixture.open_loan(collateral.clone());
+    let middle: NonFungibleLocalId = fixture.open_loan(collateral.clone());
+
+    // Health factors: 1.3, 2000 * 0.65 / 500 = 2.6 and 1000 * 0.65 / 400 = 1.625
+    let manifest: TransactionManifestV1 = fixture.radish.add_collateral(fixture.account, safe.clone(), &collateral);
+    fixture.execute(manifest).expect_commit_success();
+    let manifest: TransactionManifestV1 = fixture.radish.repay_for(fixture.account, middle.clone(), dec!(100));
+    fixture.execute(manifest).expect_commit_success();
+
+    let riskiest = |fixture: &mut LedgerFixture, limit: u32| -> Vec<NonFungibleLocalId> {
+        let manifest: TransactionManifestV1 = fixture.radish.get_riskiest_positions(fixture.account, limit);
+        let healths: Vec<PositionHealth> = fixture.execute(manifest).expect_commit_success().output(1);
+        healths.into_iter().map(|health| health.position).collect()
+    };
+    assert_eq!(riskiest(&mut fixture, 2), vec![risky.clone(), middle.clone()]);
+
+    // Repaid positions leave the index
+    let manifest: TransactionManifestV1 = fixture.radish.repay_for(fixture.account, risky, dec!(500));
+    fixture.execute(manifest).expect_commit_success();
+    assert_eq!(riskiest(&mut fixture, 5), vec![middle, safe]);
+}
+
+#[test]
+fn test_riskiest_positions_rank_by_live_health() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let falling: ResourceAddress = fixture.create_token(18, dec!(1));
+    let steady: ResourceAddress = fixture.create_token(18, dec!(1));
+    let collateral: AddrToAmount = AddrToAmount::from([(falling, dec!(1000))]);
+    let dropped: NonFungibleLocalId = fixture.open_loan(collateral.clone());
+    let risky: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(steady, dec!(1000))]));
+
+    // Indexed at 2.6 and 1.3, the price drop leaves the first at 2000 * 0.4 * 0.65 / 500 = 1.04 without reindexing it
+    let manifest: TransactionManifestV1 = fixture.radish.add_collateral(fixture.account, dropped.clone(), &collateral);
+    fixture.execute(manifest).expect_commit_success();
+    fixture.set_price(falling, dec!(0.4));
+
+    let manifest: TransactionManifestV1 = fixture.radish.get_riskiest_positions(fixture.account, 2);
+    let healths: Vec<PositionHealth> = fixture.execute(manifest).expect_commit_success().output(1);
+    let positions: Vec<NonFungibleLocalId> = healths.iter().map(|health| health.position.clone()).collect();
+    assert_eq!(positions, vec![dropped.clone(), risky.clone()]);
+    assert_eq!(healths[0].health_factor, dec!(1.04));
+
+    // Redemptions pick their positions the same way
+    let manifest: TransactionManifestV1 = fixture.radish.preview_redeem(fixture.account, dec!(600));
+    let preview: RedemptionPlan = fixture.execute(manifest).expect_commit_success().output(1);
+    let positions: Vec<NonFungibleLocalId> = preview.positions.into_iter().map(|(position, _)| position).collect();
+    assert_eq!(positions, vec![dropped, risky]);
+}
+
+#[test]
+fn test_redemption_draws_from_least_healthy_positions() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let collateral: AddrToAmount = AddrToAmount::from([(token, dec!(1000))]);
+    let risky: NonFungibleLocalId = fixture.open_loan(collateral.clone());
+    let safe: NonFungibleLocalId = fixture.open_loan(collateral.clone());
+    let middle: NonFungibleLocalId = fixture.open_loan(collateral.clone());
+
+    // Health factors 1.3, 2.6 and 1.625, 1400 RSH of debt in total
+    let manifest: TransactionManifestV1 = fixture.radish.add_collateral(fixture.account, safe.clone(), &collateral);
+    fixture.execute(manifest).expect_commit_success();
+    let manifest: TransactionManifestV1 = fixture.radish.repay_for(fixture.account, middle.clone(), dec!(100));
+    fixture.execute(manifest).expect_commit_success();
+
+    let manifest: TransactionManifestV1 = fixture.radish.preview_redeem(fixture.account, dec!(600));
+    let preview: RedemptionPlan = fixture.execute(manifest).expect_commit_success().output(1);
+    let token_before: Decimal = fixture.balance(token);
+
+    let manifest: TransactionManifestV1 = fixture.radish.redeem(fixture.account, dec!(600));
+    let receipt: TransactionReceipt = fixture.execute(manifest);
+    let redemption: RedemptionEvent = fixture
+        .events(&receipt)
+        .into_iter()
+        .find_map(|event| match event {
+            RadishEvent::Redemption(event) => Some(event),
+            _ => None,
+        })
+        .unwrap();
+
+    // The riskiest position is repaid in full, the rest comes from the next one
+    assert_eq!(redemption.positions, vec![risky.clone(), middle.clone()]);
+    assert_eq!(preview.positions[0].1.debt, dec!(0));
+    assert_eq!(preview.positions[1].1.debt, dec!(300));
+    assert_eq!(redemption.redeemed, dec!(600));
+    assert_eq!(redemption.released, preview.released);
+
+    // 600 tokens drawn at $1, less the fee
+    assert!(redemption.fee_rate > dec!("0.005"));
+    let fee: Decimal = redemption.fees[&token];
+    assert_eq!(fee + redemption.released[&token], dec!(600));
+    assert_eq!(fixture.balance(token) - token_before, redemption.released[&token]);
+
+    // Redeeming again straight away costs more
+    let manifest: TransactionManifestV1 = fixture.radish.preview_redeem(fixture.account, dec!(100));
+    let again: RedemptionPlan = fixture.execute(manifest).expect_commit_success().output(1);
+    assert!(again.fee_rate > redemption.fee_rate);
+    assert_eq!(again.positions[0].0, middle);
+}
+
+#[test]
+fn test_liquidation() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let position: NonFungibleLocalId = fixture.open_loan(AddrToAmount::from([(token, dec!(1000))]));
+
+    // Healthy positions cannot be liquidated
+    let manifest: TransactionManifestV1 = fixture.radish.liquidate(fixture.account, position.clone(), dec!(100));
+    assert!(matches!(fixture.expect_error(manifest), RadishError::PositionHealthy { .. }));
+
+    // Health factor: 1000 * 0.5 * 0.65 / 500 = 0.65
+    fixture.set_price(token, dec!(0.5));
+    let token_before: Decimal = fixture.balance(token);
+
+    let manifest: TransactionManifestV1 = fixture.radish.liquidate(fixture.account, position.clone(), dec!(500));
+    let receipt: TransactionReceipt = fixture.execute(manifest);
+    let liquidation: LiquidateEvent = fixture
+        .events(&receipt)
+        .into_iter()
+        .find_map(|event| match event {
+            RadishEvent::Liquidate(event) => Some(event),
+            _ => None,
+        })
+        .unwrap();
+
+    // Close factor limits the repayment to half of the debt, seizing its value plus a 10% bonus
+    assert_eq!(liquidation.position, position);
+    assert_eq!(liquidation.repaid, dec!(250));
+    assert_eq!(liquidation.debt, dec!(250));
+    assert_eq!(fixture.balance(token) - token_before, dec!(550));
+}
+
+#[test]
+fn test_stability_pool_absorbs_liquidations() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let collateral: AddrToAmount = AddrToAmount::from([(token, dec!(1000))]);
+    let first: NonFungibleLocalId = fixture.open_loan(collateral.clone());
+    let second: NonFungibleLocalId = fixture.open_loan(collateral.clone());
+    let third: NonFungibleLocalId = fixture.open_loan(collateral);
+
+    let deposit = |fixture: &mut LedgerFixture, amount: Decimal| -> NonFungibleLocalId {
+        let manifest: TransactionManifestV1 = fixture.radish.deposit_to_pool(fixture.account, amount);
+        let receipt: TransactionReceipt = fixture.execute(manifest);
+        fixture
+            .events(&receipt)
+            .into_iter()
+            .find_map(|event| match event {
+                RadishEvent::PoolDeposit(event) => Some(event.depositor),
+                _ => None,
+            })
+            .unwrap()
+    };
+    let pool_deposit = |fixture: &mut LedgerFixture, depositor: &NonFungibleLocalId| -> PoolDeposit {
+        let manifest: TransactionManifestV1 = fixture.radish.get_pool_deposit(fixture.account, depositor.clone());
+        fixture.execute(manifest).expect_commit_success().output(1)
+    };
+    let small: NonFungibleLocalId = deposit(&mut fixture, dec!(100));
+    let large: NonFungibleLocalId = deposit(&mut fixture, dec!(300));
+
+    // Health factors of 0.65; the pool repays half of the debt, seizing 250 * 2 * 1.1 = 550 tokens
+    fixture.set_price(token, dec!(0.5));
+    let manifest: TransactionManifestV1 = fixture.radish.liquidate_with_pool(fixture.account, first);
+    fixture.execute(manifest).expect_commit_success();
+
+    let large_deposit: PoolDeposit = pool_deposit(&mut fixture, &large);
+    assert_eq!(large_deposit.deposit, dec!(112.5));
+    assert_eq!(large_deposit.gains, AddrToAmount::from([(token, dec!(412.5))]));
+
+    // The remaining 150 RSH is not enough for half of the debt, emptying the pool for 330 more tokens
+    let manifest: TransactionManifestV1 = fixture.radish.liquidate_with_pool(fixture.account, second);
+    let receipt: TransactionReceipt = fixture.execute(manifest);
+    let absorption: PoolAbsorbEvent = fixture
+        .events(&receipt)
+        .into_iter()
+        .find_map(|event| match event {
+            RadishEvent::PoolAbsorb(event) => Some(event),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(absorption.absorbed, dec!(150));
+    assert_eq!(absorption.total_deposits, dec!(0));
+    assert_eq!(absorption.epoch, 1);
+
+    let manifest: TransactionManifestV1 = fixture.radish.liquidate_with_pool(fixture.account, third);
+    assert!(matches!(fixture.expect_error(manifest), RadishError::PoolEmpty));
+
+    // Gains are shared 1:3 across both absorptions, and outlive the emptied deposits
+    let token_before: Decimal = fixture.balance(token);
+    let manifest: TransactionManifestV1 = fixture.radish.withdraw_from_pool(fixture.account, small.clone(), dec!(100));
+    let receipt: TransactionReceipt = fixture.execute(manifest);
+    assert_eq!(fixture.balance(token) - token_before, dec!(220));
+
+    // The fully absorbed deposit only returns its gains, without an empty RSH bucket
+    let withdrawn: Vec<Bucket> = receipt.expect_commit_success().output(3);
+    assert_eq!(withdrawn.len(), 1);
+    let manifest: TransactionManifestV1 = fixture.radish.withdraw_from_pool(fixture.account, small.clone(), dec!(100));
+    let withdrawn: Vec<Bucket> = fixture.execute(manifest).expect_commit_success().output(3);
+    assert!(withdrawn.is_empty());
+
+    let small_deposit: PoolDeposit = pool_deposit(&mut fixture, &small);
+    assert_eq!(small_deposit.deposit, dec!(0));
+    assert!(small_deposit.gains.is_empty());
+    let large_deposit: PoolDeposit = pool_deposit(&mut fixture, &large);
+    assert_eq!(large_deposit.deposit, dec!(0));
+    assert_eq!(large_deposit.gains, AddrToAmount::from([(token, dec!(660))]));
+}
+
+#[test]
+fn test_previews_match_execution() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let token: ResourceAddress = fixture.create_token(18, dec!(1));
+    let collateral: AddrToAmount = AddrToAmount::from([(token, dec!(1000))]);
+
+    let manifest: TransactionManifestV1 = fixture.radish.preview_supply(fixture.account, collateral.clone());
+    let supply: PositionPreview = fixture.execute(manifest).expect_commit_success().output(1);
+    assert_eq!(supply.borrowed, dec!(500));
+    assert_eq!(supply.health_factor, dec!(1.3));
+    let position: NonFungibleLocalId = fixture.open_loan(collateral);
+
+    // Withdrawing more collateral than the margin allows is rejected by the preview as it would be by the method
+    let manifest: TransactionManifestV1 =
+        fixture.radish.preview_withdraw_collateral(fixture.account, position.clone(), token, dec!(100));
+    assert!(matches!(fixture.expect_error(manifest), RadishError::HealthBelowMargin { .. }));
+
+    fixture.set_price(token, dec!(0.5));
+    let manifest: TransactionManifestV1 = fixture.radish.preview_liquidate(fixture.account, position.clone(), dec!(500));
+    let preview: PositionPreview = fixture.execute(manifest).expect_commit_success().output(1);
+    assert_eq!(preview.fees.get(&token).copied(), Some(dec!(50)));
+
+    let manifest: TransactionManifestV1 = fixture.radish.liquidate(fixture.account, position, dec!(500));
+    let receipt: TransactionReceipt = fixture.execute(manifest);
+    let liquidation: LiquidateEvent = fixture
+        .events(&receipt)
+        .into_iter()
+        .find_map(|event| match event {
+            RadishEvent::Liquidate(event) => Some(event),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(liquidation.repaid, preview.repaid);
+    assert_eq!(liquidation.seized, preview.released);
+    assert_eq!(liquidation.collateral, preview.collateral);
+    assert_eq!(liquidation.debt, preview.debt);
+}
+
+#[test]
+fn test_admin_methods_require_admin_badge() {
+    let mut fixture: LedgerFixture = LedgerFixture::new();
+    let (_public_key, _private_key, other_account) = fixture.ledger.new_allocated_account();
+    let token: ResourceAddress = fixture.ledger.create_fungible_resource(dec!(100), 18, other_account);
+
+    let manifest: TransactionManifestV1 = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .radish_add_asset(fixture.radish.component, token, default_risk())
+        .build();
+    fixture.execute(manifest).expect_specific_failure(|error| {
+        matches!(
+            error,
+            RuntimeError::SystemModuleError(SystemModuleError::AuthError(AuthError::Unauthorized(..)))
+        )
+    });
+
+    let manifest: TransactionManifestV1 = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .radish_set_price(fixture.radish.component, XRD, dec!(100))
+        .build();
+    fixture.execute(manifest).expect_specific_failure(|error| {
+        matches!(
+            error,
+            RuntimeError::SystemModuleError(SystemModuleError::AuthError(AuthError::Unauthorized(..)))
+        )
+    });
+}
+
+/* ------------- Test Environment ------------- */
+#[test]
+fn test_borrow_and_repay_with_test_environment() -> Result<(), RuntimeError> {
+    let mut fixture: EnvFixture = EnvFixture::new()?;
+    let collateral: Bucket = fixture.create_token(dec!(1000), 18, dec!(1))?;
+    let token: ResourceAddress = collateral.resource_address(&mut fixture.env)?;
+
+    let (borrower_badge, rsh) = fixture.radish.get_loan(vec![collateral], &mut fixture.env)?;
+    assert_eq!(rsh.amount(&mut fixture.env)?, dec!(500));
+
+    let position_proof: NonFungibleProof = NonFungibleProof(borrower_badge.create_proof_of_all(&mut fixture.env)?);
+    let released: Vec<Bucket> = fixture.radish.repay_loan(position_proof, rsh, &mut fixture.env)?;
+    let mut released_collateral: Decimal = dec!(0);
+    for bucket in released {
+        if bucket.resource_address(&mut fixture.env)? == token {
+            released_collateral += bucket.amount(&mut fixture.env)?;
+        }
+    }
+    assert_eq!(released_collateral, dec!(1000));
+
+    Ok(())
+}
+
+#[test]
+fn test_liquidation_with_test_environment() -> Result<(), RuntimeError> {
+    let mut fixture: EnvFixture = EnvFixture::new()?;
+    let collateral: Bucket = fixture.create_token(dec!(1000), 18, dec!(1))?;
+    let token: ResourceAddress = collateral.resource_address(&mut fixture.env)?;
+
+    let (borrower_badge, rsh) = fixture.radish.get_loan(vec![collateral], &mut fixture.env)?;
+    let position: NonFungibleLocalId = borrower_badge
+        .non_fungible_local_ids(&mut fixture.env)?
+        .into_iter()
+        .next()
+        .unwrap();
+
+    fixture.radish.set_price(token, dec!(0.5), &mut fixture.env)?;
+    let released: Vec<Bucket> = fixture.radish.liquidate(position, rsh, &mut fixture.env)?;
+
+    let mut seized: Decimal = dec!(0);
+    let mut refund: Decimal = dec!(0);
+    for bucket in released {
+        let amount: Decimal = bucket.amount(&mut fixture.env)?;
+        if bucket.resource_address(&mut fixture.env)? == token {
+            seized += amount;
+        } else {
+            refund += amount;
+        }
+    }
+    assert_eq!(seized, dec!(550));
+    assert_eq!(refund, dec!(250));
+
+    Ok(())
+}
+
+#[test]
+fn test_mock_token_faucet_is_rate_limited() -> Result<(), RuntimeError> {
+    let mut fixture: EnvFixture = EnvFixture::new()?;
+    let mut faucet: MockToken =
+        MockToken::instantiate("Tether USD".to_string(), "USDT".to_string(), 6, dec!(100), 86400, fixture.package, &mut fixture.env)?;
+
+    let bucket: Bucket = faucet.free_tokens(dec!(60), &mut fixture.env)?;
+    assert_eq!(bucket.amount(&mut fixture.env)?, dec!(60));
+    assert_eq!(faucet.token_address(&mut fixture.env)?, bucket.resource_address(&mut fixture.env)?);
+
+    // Only 40 tokens are left in the current window
+    assert!(faucet.free_tokens(dec!(60), &mut fixture.env).is_err());
+
+    Ok(())
+}